@@ -2,6 +2,8 @@ use clap::{Parser, Subcommand, ValueEnum};
 use clap_complete::Shell;
 use std::path::PathBuf;
 
+use crate::types::KeyOrder;
+
 #[derive(Clone, Debug, ValueEnum)]
 pub enum ColorMode {
     Auto,
@@ -9,6 +11,31 @@ pub enum ColorMode {
     Never,
 }
 
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum InputFormat {
+    /// A single JSON document
+    Json,
+    /// Newline-delimited JSON, one sample per line
+    #[value(alias = "jsonl")]
+    Ndjson,
+    /// JSON with comments, trailing commas, single quotes and unquoted keys
+    #[value(alias = "jsonc")]
+    Json5,
+    /// YAML, one sample per document
+    #[value(alias = "yml")]
+    Yaml,
+    /// Comma- (or --delimiter-) separated values, one sample per row
+    Csv,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ReportFormat {
+    /// Colored table for the terminal
+    Table,
+    /// JSON document for scripts
+    Json,
+}
+
 #[derive(Subcommand, Debug)]
 pub enum Command {
     /// Generate shell completion scripts
@@ -36,7 +63,15 @@ extracts nested objects as separate types, and handles union types in arrays.",
   mold user.json --zod --strict --name User
   cat data.json | mold --ts --name MyType
   mold a.json b.json --ts -o ./generated
-  mold data.json --ts --export --readonly"
+  mold responses/*.json --merge --zod --name User
+  mold data.json --ts --export --readonly
+  mold orders.json --zod --enums
+  mold cache.json --ts --map entries
+  mold orders.json --zod --config mold.json
+  mold events.ndjson --ts --name Event
+  mold users.csv --prisma --zod
+  mold samples/*.json --merge --report
+  mold export.json --ts --sample 1000"
 )]
 pub struct Args {
     #[command(subcommand)]
@@ -46,6 +81,20 @@ pub struct Args {
     #[arg(value_name = "FILE")]
     pub files: Vec<PathBuf>,
 
+    /// Input format (default: detected from the file extension, .ndjson/.jsonl → ndjson,
+    /// .json5/.jsonc → json5, .yaml/.yml → yaml, .csv/.tsv → csv)
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    pub input_format: Option<InputFormat>,
+
+    /// Column delimiter for CSV input, a single character or `tab` (default: tab for
+    /// .tsv files, comma otherwise)
+    #[arg(long, value_name = "CHAR", value_parser = parse_delimiter)]
+    pub delimiter: Option<u8>,
+
+    /// CSV input has no header row; columns are named column1, column2, ...
+    #[arg(long)]
+    pub no_header: bool,
+
     // -- Output Formats --
     /// Generate TypeScript interfaces
     #[arg(short = 't', long, help_heading = "Output Formats")]
@@ -72,10 +121,46 @@ pub struct Args {
     #[arg(short = 'n', long, value_name = "NAME", help_heading = "Output")]
     pub name: Option<String>,
 
+    /// Merge all inputs into a single schema instead of one per file
+    #[arg(short = 'm', long, help_heading = "Output")]
+    pub merge: bool,
+
+    /// Order of the fields in generated types
+    #[arg(
+        long,
+        value_enum,
+        value_name = "ORDER",
+        default_value = "alphabetical",
+        help_heading = "Output"
+    )]
+    pub key_order: KeyOrder,
+
+    /// Print per-path statistics (presence, nulls, types, ranges, examples)
+    /// instead of generated code
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "table",
+        help_heading = "Output"
+    )]
+    pub report: Option<ReportFormat>,
+
     /// Keep nested objects inline (no extraction)
     #[arg(long, help_heading = "Output")]
     pub flat: bool,
 
+    /// Type numeric and boolean strings as numbers and booleans (`z.coerce` in Zod)
+    #[arg(long, help_heading = "Output")]
+    pub coerce: bool,
+
+    /// Emit observed ranges, lengths and array sizes as constraints
+    /// (Zod `.min()`/`.max()`, Prisma `@db.VarChar(n)`, JSDoc tags)
+    #[arg(long, help_heading = "Output")]
+    pub constraints: bool,
+
     /// Control color output
     #[arg(long, value_enum, default_value_t = ColorMode::Auto, help_heading = "Output")]
     pub color: ColorMode,
@@ -92,6 +177,63 @@ pub struct Args {
     #[arg(short = 'w', long, help_heading = "Output")]
     pub watch: bool,
 
+    // -- Inference --
+    /// Infer string enums from fields with a small set of repeated values
+    #[arg(long, help_heading = "Inference")]
+    pub enums: bool,
+
+    /// Maximum distinct values for a field to become an enum
+    #[arg(long, value_name = "N", default_value_t = 10, help_heading = "Inference")]
+    pub enum_max_values: usize,
+
+    /// Minimum observed values before a field can become an enum
+    #[arg(long, value_name = "N", default_value_t = 3, help_heading = "Inference")]
+    pub enum_min_occurrences: usize,
+
+    /// Treat the object at PATH as a map, e.g. `data.users` or `items[].tags` (repeatable)
+    #[arg(long = "map", value_name = "PATH", help_heading = "Inference")]
+    pub map_paths: Vec<String>,
+
+    /// Minimum ID- or date-like keys for an object to be detected as a map
+    #[arg(long, value_name = "N", default_value_t = 3, help_heading = "Inference")]
+    pub map_min_keys: usize,
+
+    /// Infer from only the first N elements of a top-level array
+    #[arg(long, value_name = "N", help_heading = "Inference")]
+    pub sample: Option<usize>,
+
+    /// Infer from N elements of a top-level array picked at random
+    /// (reproducible reservoir sampling)
+    #[arg(long, value_name = "N", conflicts_with = "sample", help_heading = "Inference")]
+    pub reservoir: Option<usize>,
+
+    /// Infer integers in timestamp-named fields (`created_at`, `expires`)
+    /// as Unix timestamps
+    #[arg(long, help_heading = "Inference")]
+    pub detect_timestamps: bool,
+
+    /// Merge objects tagged by `type`/`kind`/`event`/`__typename` into one type instead
+    /// of a discriminated union
+    #[arg(long, help_heading = "Inference")]
+    pub no_discriminated_unions: bool,
+
+    /// Skip unique/readonly/foreign-key hints from names like `id` and `userId`
+    #[arg(long, help_heading = "Inference")]
+    pub no_heuristics: bool,
+
+    /// Read field tags (@unique, @readonly, @optional, @nullable, @description, @default,
+    /// @type) from comments next to fields; JSON input is read as JSON5
+    #[arg(long, help_heading = "Inference")]
+    pub annotations: bool,
+
+    /// JSON config file with custom string formats and disabled built-ins
+    #[arg(long, value_name = "FILE", help_heading = "Inference")]
+    pub config: Option<PathBuf>,
+
+    /// Switch off a built-in string format, e.g. `email` (repeatable)
+    #[arg(long = "disable-format", value_name = "NAME", help_heading = "Inference")]
+    pub disable_formats: Vec<String>,
+
     // -- TypeScript Options --
     /// Add 'export' keyword to TypeScript interfaces
     #[arg(long = "export", help_heading = "TypeScript Options")]
@@ -106,3 +248,11 @@ pub struct Args {
     #[arg(long = "strict", help_heading = "Zod Options")]
     pub zod_strict: bool,
 }
+
+fn parse_delimiter(s: &str) -> Result<u8, String> {
+    match s {
+        "tab" | "\\t" => Ok(b'\t'),
+        _ if s.len() == 1 && s.is_ascii() => Ok(s.as_bytes()[0]),
+        _ => Err(format!("expected a single ASCII character or `tab`, got '{}'", s)),
+    }
+}
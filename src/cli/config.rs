@@ -0,0 +1,70 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::Path;
+
+use crate::parser::NameHeuristics;
+use crate::types::CustomFormat;
+
+/// Settings read from the JSON file given with `--config`
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ConfigFile {
+    /// User-defined string formats, tried in order before the built-in ones
+    #[serde(default)]
+    pub formats: Vec<CustomFormat>,
+    /// Built-in formats to switch off (e.g., `email`)
+    #[serde(default)]
+    pub disable_formats: Vec<String>,
+    /// Field-name rules for unique, readonly and foreign-key metadata
+    #[serde(default)]
+    pub heuristics: NameHeuristics,
+}
+
+impl ConfigFile {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file '{}'", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Invalid config file '{}'", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let config: ConfigFile = serde_json::from_str(
+            r#"{
+                "formats": [
+                    {"name": "OrderNumber", "pattern": "^ORD-\\d{8}$", "prismaAttribute": "@db.VarChar(12)"}
+                ],
+                "disableFormats": ["email"]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.formats[0].name, "OrderNumber");
+        assert_eq!(config.formats[0].prisma_type, "String");
+        assert_eq!(
+            config.formats[0].prisma_attribute.as_deref(),
+            Some("@db.VarChar(12)")
+        );
+        assert_eq!(config.disable_formats, vec!["email"]);
+        assert!(config.heuristics.enabled);
+    }
+
+    #[test]
+    fn test_partial_heuristics_keep_defaults() {
+        let config: ConfigFile =
+            serde_json::from_str(r#"{"heuristics": {"unique": ["sku"]}}"#).unwrap();
+        assert_eq!(config.heuristics.unique, vec!["sku"]);
+        assert_eq!(config.heuristics.readonly, vec!["id", "created_at"]);
+    }
+
+    #[test]
+    fn test_unknown_keys_are_rejected() {
+        assert!(serde_json::from_str::<ConfigFile>(r#"{"format": []}"#).is_err());
+    }
+}
@@ -1,3 +1,5 @@
 mod args;
+mod config;
 
-pub use args::{Args, ColorMode, Command};
+pub use args::{Args, ColorMode, Command, InputFormat, ReportFormat};
+pub use config::ConfigFile;
@@ -16,6 +16,8 @@ pub struct GeneratorConfig {
     pub ts_export_interfaces: bool,
     pub ts_readonly_fields: bool,
     pub zod_strict_objects: bool,
+    /// Type numeric and boolean strings as the values they hold, coercing in Zod
+    pub coerce_strings: bool,
     pub prisma_generate_relations: bool,
 }
 
@@ -27,6 +29,7 @@ impl Default for GeneratorConfig {
             ts_export_interfaces: false,
             ts_readonly_fields: false,
             zod_strict_objects: false,
+            coerce_strings: false,
             prisma_generate_relations: true,
         }
     }
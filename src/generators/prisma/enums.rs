@@ -0,0 +1,156 @@
+use crate::types::{Field, ObjectType, SchemaType};
+use crate::utils::{quote_string, sanitize_identifier, to_pascal_case};
+use std::collections::HashSet;
+
+use super::relations::{format_field_name, format_model_name, generate_field_attributes};
+
+/// Enum values of a field typed as an enum (or a list of one). A single
+/// value is a union variant's tag and stays a plain string.
+pub fn field_enum_values(field: &Field) -> Option<&[String]> {
+    match &field.field_type {
+        SchemaType::Enum(values) if values.len() > 1 => Some(values),
+        SchemaType::Array(inner) => match inner.as_ref() {
+            SchemaType::Enum(values) => Some(values),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Name of the Prisma enum backing a field (e.g., User + status → UserStatus)
+pub fn enum_type_name(model_name: &str, field: &Field) -> String {
+    format!(
+        "{}{}",
+        format_model_name(model_name),
+        to_pascal_case(&sanitize_identifier(&field.name))
+    )
+}
+
+/// All enums needed by the fields of a model, in field order
+pub fn collect_enums(model_name: &str, obj: &ObjectType) -> Vec<(String, Vec<String>)> {
+    obj.fields
+        .iter()
+        .filter_map(|field| {
+            field_enum_values(field).map(|values| (enum_type_name(model_name, field), values.to_vec()))
+        })
+        .collect()
+}
+
+pub fn generate_enum_field(field: &Field, enum_name: &str, indent: &str) -> String {
+    let field_name = format_field_name(&field.name);
+    if matches!(field.field_type, SchemaType::Array(_)) {
+        return format!("{}{} {}[]", indent, field_name, enum_name);
+    }
+    let optional = if field.optional || field.nullable { "?" } else { "" };
+    format!(
+        "{}{} {}{}{}",
+        indent,
+        field_name,
+        enum_name,
+        optional,
+        generate_field_attributes(field)
+    )
+}
+
+/// Render an enum block. Values that aren't valid identifiers are sanitized
+/// and mapped back to the original string with `@map`.
+pub fn generate_enum(name: &str, values: &[String], indent: &str) -> String {
+    let mut lines = vec![format!("enum {} {{", name)];
+    let mut taken = HashSet::new();
+    for value in values {
+        let base = enum_member_name(value);
+        let mut ident = base.clone();
+        let mut n = 2;
+        while !taken.insert(ident.clone()) {
+            ident = format!("{}_{}", base, n);
+            n += 1;
+        }
+        if &ident == value {
+            lines.push(format!("{}{}", indent, ident));
+        } else {
+            lines.push(format!("{}{} @map({})", indent, ident, quote_string(value)));
+        }
+    }
+    lines.push("}".to_string());
+    lines.join("\n")
+}
+
+/// A value as a Prisma enum member, which has to start with a letter and
+/// can only hold ASCII letters, digits and underscores
+fn enum_member_name(value: &str) -> String {
+    if value.is_empty() {
+        return "empty".to_string();
+    }
+    let ident: String = value
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if ident.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        ident
+    } else {
+        format!("v{}", ident)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status_enum() -> SchemaType {
+        SchemaType::Enum(vec!["active".to_string(), "in-review".to_string()])
+    }
+
+    #[test]
+    fn test_enum_type_name() {
+        let field = Field::new("status", status_enum());
+        assert_eq!(enum_type_name("user", &field), "UserStatus");
+        let field = Field::new("order_state", status_enum());
+        assert_eq!(enum_type_name("Order", &field), "OrderOrderState");
+    }
+
+    #[test]
+    fn test_collect_enums_includes_arrays() {
+        let obj = ObjectType::new(vec![
+            Field::new("name", SchemaType::String),
+            Field::new("status", status_enum()),
+            Field::new("roles", SchemaType::Array(Box::new(status_enum()))),
+        ]);
+        let enums = collect_enums("User", &obj);
+        let names: Vec<&str> = enums.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, vec!["UserStatus", "UserRoles"]);
+    }
+
+    #[test]
+    fn test_generate_enum_maps_invalid_identifiers() {
+        let output = generate_enum(
+            "UserStatus",
+            &["active".to_string(), "in-review".to_string()],
+            "  ",
+        );
+        assert_eq!(
+            output,
+            "enum UserStatus {\n  active\n  in_review @map(\"in-review\")\n}"
+        );
+    }
+
+    #[test]
+    fn test_generate_enum_makes_member_names_unique_and_valid() {
+        let values: Vec<String> = ["a-b", "a_b", "", "1x", "a_b_2"]
+            .iter()
+            .map(|v| v.to_string())
+            .collect();
+        assert_eq!(
+            generate_enum("Kind", &values, "  "),
+            "enum Kind {\n  a_b @map(\"a-b\")\n  a_b_2 @map(\"a_b\")\n  empty @map(\"\")\n  v1x @map(\"1x\")\n  a_b_2_2 @map(\"a_b_2\")\n}"
+        );
+    }
+
+    #[test]
+    fn test_generate_enum_field() {
+        let field = Field::new("status", status_enum()).optional();
+        assert_eq!(generate_enum_field(&field, "UserStatus", "  "), "  status UserStatus?");
+
+        let field = Field::new("roles", SchemaType::Array(Box::new(status_enum())));
+        assert_eq!(generate_enum_field(&field, "UserRoles", "  "), "  roles UserRoles[]");
+    }
+}
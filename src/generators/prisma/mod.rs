@@ -1,12 +1,16 @@
+mod enums;
 mod relations;
 mod types;
 
 use crate::generators::{Generator, GeneratorConfig};
 use crate::types::{NestedType, ObjectType, Schema, SchemaType};
+use crate::utils::{comment_text, quote_string};
 use anyhow::Result;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use relations::{format_model_name, generate_field};
+use enums::{collect_enums, enum_type_name, field_enum_values, generate_enum, generate_enum_field};
+use relations::{format_field_name, format_model_name, generate_field, generate_self_relation};
+use types::stored_field;
 
 pub struct PrismaGenerator;
 
@@ -19,16 +23,45 @@ impl PrismaGenerator {
         &self,
         name: &str,
         obj: &ObjectType,
-        indent: &str,
         type_refs: &HashMap<String, String>,
-        generate_relations: bool,
+        config: &GeneratorConfig,
     ) -> String {
+        let indent = config.indent.as_str();
+        let generate_relations = config.prisma_generate_relations;
         let model_name = format_model_name(name);
         let mut lines = vec![format!("model {} {{", model_name)];
 
         lines.push(format!("{}id Int @id @default(autoincrement())", indent));
 
         for field in &obj.fields {
+            let mut field = stored_field(field, config.coerce_strings);
+            // The synthetic primary key above is already unique
+            if format_field_name(&field.name) == "id" {
+                field.metadata.is_unique = false;
+            }
+            let field = &field;
+            if let Some(description) = &field.metadata.description {
+                lines.push(format!("{}/// {}", indent, comment_text(description)));
+            }
+            if let Some(value) = obj.literal_value(&field.name) {
+                lines.push(format!(
+                    "{}{} String @default({})",
+                    indent,
+                    format_field_name(&field.name),
+                    quote_string(value)
+                ));
+                continue;
+            }
+            if field_enum_values(field).is_some() {
+                lines.push(generate_enum_field(field, &enum_type_name(name, field), indent));
+                continue;
+            }
+            if generate_relations {
+                if let Some(field_lines) = generate_self_relation(field, &model_name, indent) {
+                    lines.extend(field_lines);
+                    continue;
+                }
+            }
             if let Some(field_lines) = generate_field(field, indent, type_refs, generate_relations) {
                 for line in field_lines {
                     lines.push(line);
@@ -49,19 +82,99 @@ impl PrismaGenerator {
         refs
     }
 
+    /// The nested types that become models: those the root reaches through
+    /// object fields and arrays of objects. Types found only inside a map,
+    /// tuple or union are stored in that field's `Json` column instead.
+    fn model_types<'a>(
+        &self,
+        schema: &'a Schema,
+        type_refs: &HashMap<String, String>,
+    ) -> Vec<&'a NestedType> {
+        let by_name: HashMap<&str, &ObjectType> = schema
+            .nested_types
+            .iter()
+            .map(|nt| (nt.name.as_str(), &nt.object))
+            .collect();
+        let mut reached = HashSet::new();
+        let mut pending: Vec<&ObjectType> = schema.root_object().into_iter().collect();
+        // Each variant of a discriminated union root is a model of its own
+        let root = match &schema.root_type {
+            SchemaType::Array(inner) => inner.as_ref(),
+            other => other,
+        };
+        if let SchemaType::DiscriminatedUnion { variants, .. } = root {
+            for variant in variants {
+                if let Some(name) = type_refs.get(&format!("{:?}", variant)) {
+                    reached.insert(name.clone());
+                    pending.push(variant);
+                }
+            }
+        }
+        while let Some(obj) = pending.pop() {
+            for field in &obj.fields {
+                let Some(name) = related_type(&field.field_type, type_refs) else {
+                    continue;
+                };
+                if let Some(object) = by_name.get(name) {
+                    if reached.insert(name.to_string()) {
+                        pending.push(object);
+                    }
+                }
+            }
+        }
+        schema
+            .nested_types
+            .iter()
+            .filter(|nt| reached.contains(&nt.name))
+            .collect()
+    }
+
     fn generate_nested_models(
         &self,
-        nested_types: &[NestedType],
-        indent: &str,
+        nested_types: &[&NestedType],
         type_refs: &HashMap<String, String>,
-        generate_relations: bool,
+        config: &GeneratorConfig,
     ) -> Vec<String> {
         nested_types
             .iter()
             .rev()
-            .map(|nt| self.generate_model(&nt.name, &nt.object, indent, type_refs, generate_relations))
+            .map(|nt| self.generate_model(&nt.name, &nt.object, type_refs, config))
             .collect()
     }
+
+    fn collect_all_enums(
+        &self,
+        schema: &Schema,
+        models: &[&NestedType],
+    ) -> Vec<(String, Vec<String>)> {
+        let mut enums = Vec::new();
+        for nt in models.iter().rev() {
+            enums.extend(collect_enums(&nt.name, &nt.object));
+        }
+        if let Some(obj) = schema.root_object() {
+            enums.extend(collect_enums(&schema.root_object_name(), obj));
+        }
+        let mut seen = HashSet::new();
+        enums.retain(|(name, _)| seen.insert(name.clone()));
+        enums
+    }
+}
+
+/// The nested type a field's relation points at, if it has one
+fn related_type<'a>(
+    field_type: &'a SchemaType,
+    type_refs: &'a HashMap<String, String>,
+) -> Option<&'a str> {
+    match field_type {
+        SchemaType::Object(obj) => type_refs.get(&format!("{:?}", obj)).map(String::as_str),
+        SchemaType::Ref(name) => Some(name),
+        SchemaType::Optional(inner) => related_type(inner, type_refs),
+        SchemaType::Array(inner) => match inner.as_ref() {
+            SchemaType::Object(_) | SchemaType::Ref(_) => related_type(inner, type_refs),
+            _ => None,
+        },
+        _ => None,
+    }
 }
 
 impl Default for PrismaGenerator {
@@ -80,29 +193,34 @@ impl Generator for PrismaGenerator {
             self.build_type_refs(&schema.nested_types)
         };
 
-        if !config.flat_mode && !schema.nested_types.is_empty() {
-            let nested = self.generate_nested_models(
-                &schema.nested_types,
-                &config.indent,
-                &type_refs,
-                config.prisma_generate_relations,
-            );
+        let models = if config.flat_mode {
+            Vec::new()
+        } else {
+            self.model_types(schema, &type_refs)
+        };
+
+        if !models.is_empty() {
+            let nested = self.generate_nested_models(&models, &type_refs, config);
             for model in nested {
                 output.push(model);
                 output.push(String::new());
             }
         }
 
-        if let SchemaType::Object(obj) = &schema.root_type {
+        if let Some(obj) = schema.root_object() {
             output.push(self.generate_model(
-                &schema.name,
+                &schema.root_object_name(),
                 obj,
-                &config.indent,
                 &type_refs,
-                config.prisma_generate_relations,
+                config,
             ));
         }
 
+        for (name, values) in self.collect_all_enums(schema, &models) {
+            output.push(String::new());
+            output.push(generate_enum(&name, &values, &config.indent));
+        }
+
         Ok(output.join("\n"))
     }
 
@@ -114,7 +232,7 @@ impl Generator for PrismaGenerator {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::Field;
+    use crate::types::{Field, SchemaType};
 
     #[test]
     fn test_generate_simple_model() {
@@ -284,6 +402,21 @@ mod tests {
         assert!(output.contains("id Int @id @default(autoincrement())"));
     }
 
+    #[test]
+    fn test_data_id_field_is_not_unique() {
+        let gen = PrismaGenerator::new();
+        let mut id = Field::new("id", SchemaType::Integer);
+        id.metadata.is_unique = true;
+        let obj = ObjectType::new(vec![id, Field::new("name", SchemaType::String)]);
+        let schema = Schema::new("Test", SchemaType::Object(obj));
+        let config = GeneratorConfig::default();
+
+        let output = gen.generate(&schema, &config).unwrap();
+
+        assert!(output.contains("id Int @id @default(autoincrement())"));
+        assert!(!output.contains("@unique"));
+    }
+
     #[test]
     fn test_pascal_case_model_name() {
         let gen = PrismaGenerator::new();
@@ -296,4 +429,51 @@ mod tests {
 
         assert!(output.contains("model Model {"));
     }
+
+    #[test]
+    fn test_enum_field_generates_enum_block() {
+        let gen = PrismaGenerator::new();
+        let obj = ObjectType::new(vec![Field::new(
+            "status",
+            SchemaType::Enum(vec!["active".to_string(), "pending".to_string()]),
+        )]);
+        let schema = Schema::new("User", SchemaType::Object(obj));
+        let config = GeneratorConfig::default();
+
+        let output = gen.generate(&schema, &config).unwrap();
+
+        assert!(output.contains("status UserStatus"));
+        assert!(output.contains("enum UserStatus {\n  active\n  pending\n}"));
+    }
+
+    #[test]
+    fn test_array_root_generates_element_model() {
+        let gen = PrismaGenerator::new();
+        let obj = ObjectType::new(vec![Field::new("name", SchemaType::String)]);
+        let schema = Schema::new(
+            "Users",
+            SchemaType::Array(Box::new(SchemaType::Object(obj))),
+        );
+        let config = GeneratorConfig::default();
+
+        let output = gen.generate(&schema, &config).unwrap();
+
+        assert!(output.contains("model User {"));
+        assert!(!output.contains("model Users"));
+    }
+
+    #[test]
+    fn test_map_field_is_json() {
+        let gen = PrismaGenerator::new();
+        let obj = ObjectType::new(vec![Field::new(
+            "scores",
+            SchemaType::Map(Box::new(SchemaType::Integer)),
+        )]);
+        let schema = Schema::new("Test", SchemaType::Object(obj));
+        let config = GeneratorConfig::default();
+
+        let output = gen.generate(&schema, &config).unwrap();
+
+        assert!(output.contains("scores Json"));
+    }
 }
@@ -1,5 +1,5 @@
 use crate::types::{Field, SchemaType};
-use crate::utils::{is_prisma_reserved, sanitize_identifier, to_pascal_case};
+use crate::utils::{default_literal, is_prisma_reserved, sanitize_identifier, to_pascal_case};
 use std::collections::HashMap;
 
 use super::types::generate_prisma_type;
@@ -24,6 +24,25 @@ pub fn generate_field_attributes(field: &Field) -> String {
         }
     }
 
+    if let SchemaType::Custom(format) = &field.field_type {
+        attrs.extend(format.prisma_attribute.clone());
+    }
+
+    // `VarChar(0)` can't hold anything, so fields only ever seen empty keep
+    // the default column type
+    if let (SchemaType::String, Some(max @ 1..)) =
+        (&field.field_type, field.metadata.constraints.max_length)
+    {
+        attrs.push(format!("@db.VarChar({})", max));
+    }
+
+    if let Some(value) = &field.metadata.default_value {
+        // An explicit default replaces the one guessed from the field name
+        attrs.retain(|attr| !attr.starts_with("@default"));
+        let value = default_literal(value).unwrap_or_else(|| value.clone());
+        attrs.push(format!("@default({})", value));
+    }
+
     if attrs.is_empty() {
         String::new()
     } else {
@@ -38,6 +57,7 @@ pub fn generate_field(
     generate_relations: bool,
 ) -> Option<Vec<String>> {
     let field_name = format_field_name(&field.name);
+    let optional = if field.optional || field.nullable { "?" } else { "" };
     let mut lines = Vec::new();
 
     match &field.field_type {
@@ -47,21 +67,44 @@ pub fn generate_field(
             }
 
             if obj.fields.is_empty() {
-                let optional = if field.optional { "?" } else { "" };
                 lines.push(format!("{}{} Json{}", indent, field_name, optional));
             } else {
                 let related_model = type_refs
                     .get(&format!("{:?}", obj))
                     .map(|s| format_model_name(s))
                     .unwrap_or_else(|| format_model_name(&field.name));
-                let optional = if field.optional { "?" } else { "" };
 
                 lines.push(format!("{}{} {}{}", indent, field_name, related_model, optional));
                 lines.push(format!("{}{}Id Int{} @unique", indent, field_name, optional));
             }
         }
+        SchemaType::Ref(target) => {
+            if !generate_relations {
+                return None;
+            }
+            // A reference back to an ancestor can't be required, or no
+            // record could ever be created
+            lines.push(format!(
+                "{}{} {}?",
+                indent,
+                field_name,
+                format_model_name(target)
+            ));
+            lines.push(format!("{}{}Id Int? @unique", indent, field_name));
+        }
         SchemaType::Array(inner) => {
-            if let SchemaType::Object(obj) = inner.as_ref() {
+            if let SchemaType::Ref(target) = inner.as_ref() {
+                if generate_relations {
+                    lines.push(format!(
+                        "{}{} {}[]",
+                        indent,
+                        field_name,
+                        format_model_name(target)
+                    ));
+                } else {
+                    lines.push(format!("{}{} Json", indent, field_name));
+                }
+            } else if let SchemaType::Object(obj) = inner.as_ref() {
                 if !generate_relations {
                     lines.push(format!("{}{} Json", indent, field_name));
                 } else if obj.fields.is_empty() {
@@ -79,7 +122,6 @@ pub fn generate_field(
         }
         _ => {
             if let Some(prisma_type) = generate_prisma_type(&field.field_type) {
-                let optional = if field.optional { "?" } else { "" };
                 let attrs = generate_field_attributes(field);
                 lines.push(format!(
                     "{}{} {}{}{}",
@@ -96,6 +138,54 @@ pub fn generate_field(
     }
 }
 
+/// Render a field that refers back to its own model. Prisma requires both
+/// sides of a self-relation, so the opposite side is emitted alongside it:
+/// `children Comment[]` gets a `childrenParent Comment?` back-reference and
+/// `parent Category?` gets a `parentOf Category?`.
+pub fn generate_self_relation(field: &Field, model_name: &str, indent: &str) -> Option<Vec<String>> {
+    let (target, is_list) = match &field.field_type {
+        SchemaType::Ref(target) => (target, false),
+        SchemaType::Array(inner) => match inner.as_ref() {
+            SchemaType::Ref(target) => (target, true),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    if format_model_name(target) != model_name {
+        return None;
+    }
+
+    let field_name = format_field_name(&field.name);
+    let relation = format!("{}{}", model_name, to_pascal_case(&field.name));
+    let lines = if is_list {
+        let back = format!("{}Parent", field_name);
+        vec![
+            format!(
+                "{}{} {}[] @relation(\"{}\")",
+                indent, field_name, model_name, relation
+            ),
+            format!(
+                "{}{} {}? @relation(\"{}\", fields: [{}Id], references: [id])",
+                indent, back, model_name, relation, back
+            ),
+            format!("{}{}Id Int?", indent, back),
+        ]
+    } else {
+        vec![
+            format!(
+                "{}{} {}? @relation(\"{}\", fields: [{}Id], references: [id])",
+                indent, field_name, model_name, relation, field_name
+            ),
+            format!("{}{}Id Int? @unique", indent, field_name),
+            format!(
+                "{}{}Of {}? @relation(\"{}\")",
+                indent, field_name, model_name, relation
+            ),
+        ]
+    };
+    Some(lines)
+}
+
 pub fn format_field_name(name: &str) -> String {
     let sanitized = sanitize_identifier(name);
     if is_prisma_reserved(&sanitized) {
@@ -165,6 +255,22 @@ mod tests {
         assert_eq!(generate_field_attributes(&field), "");
     }
 
+    #[test]
+    fn test_field_attributes_varchar_from_max_length() {
+        let mut metadata = FieldMetadata::new();
+        metadata.constraints.max_length = Some(40);
+        let field = Field::new("name", SchemaType::String).with_metadata(metadata);
+        assert_eq!(generate_field_attributes(&field), " @db.VarChar(40)");
+    }
+
+    #[test]
+    fn test_field_attributes_no_varchar_for_empty_strings() {
+        let mut metadata = FieldMetadata::new();
+        metadata.constraints.max_length = Some(0);
+        let field = Field::new("note", SchemaType::String).with_metadata(metadata);
+        assert_eq!(generate_field_attributes(&field), "");
+    }
+
     #[test]
     fn test_field_attributes_unique() {
         let mut metadata = FieldMetadata::new();
@@ -219,6 +325,14 @@ mod tests {
         assert_eq!(result, Some(vec!["  name String".to_string()]));
     }
 
+    #[test]
+    fn test_generate_field_nullable_is_optional() {
+        let field = Field::new("bio", SchemaType::String).nullable();
+        let refs = HashMap::new();
+        let result = generate_field(&field, "  ", &refs, true);
+        assert_eq!(result, Some(vec!["  bio String?".to_string()]));
+    }
+
     #[test]
     fn test_generate_field_null_returns_none() {
         let field = Field::new("nothing", SchemaType::Null);
@@ -255,4 +369,46 @@ mod tests {
         let result = generate_field(&field, "  ", &refs, false);
         assert_eq!(result, Some(vec!["  items Json".to_string()]));
     }
+
+    // --- self-relation tests ---
+
+    #[test]
+    fn test_self_relation_list() {
+        let field = Field::new(
+            "children",
+            SchemaType::Array(Box::new(SchemaType::Ref("Category".to_string()))),
+        );
+        let lines = generate_self_relation(&field, "Category", "  ").unwrap();
+        assert_eq!(
+            lines,
+            vec![
+                "  children Category[] @relation(\"CategoryChildren\")",
+                "  childrenParent Category? @relation(\"CategoryChildren\", fields: [childrenParentId], references: [id])",
+                "  childrenParentId Int?",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_self_relation_single() {
+        let field = Field::new("parent", SchemaType::Ref("Category".to_string())).nullable();
+        let lines = generate_self_relation(&field, "Category", "  ").unwrap();
+        assert_eq!(
+            lines,
+            vec![
+                "  parent Category? @relation(\"CategoryParent\", fields: [parentId], references: [id])",
+                "  parentId Int? @unique",
+                "  parentOf Category? @relation(\"CategoryParent\")",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ref_to_other_model_is_plain_relation() {
+        let field = Field::new("root", SchemaType::Ref("Tree".to_string()));
+        assert!(generate_self_relation(&field, "TreeNode", "  ").is_none());
+
+        let lines = generate_field(&field, "  ", &HashMap::new(), true).unwrap();
+        assert_eq!(lines, vec!["  root Tree?", "  rootId Int? @unique"]);
+    }
 }
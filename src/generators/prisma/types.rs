@@ -1,29 +1,97 @@
-use crate::types::SchemaType;
+use crate::types::{Field, SchemaType};
+
+/// Integer strings (`"42"`) are stored as text, since they're as often codes
+/// as counts, unless coercion is on: then as `Int`, or `BigInt` when the
+/// values seen overflow 32 bits
+pub fn stored_field(field: &Field, coerce_strings: bool) -> Field {
+    let mut field = field.clone();
+    field.field_type = stored_type(&field.field_type, coerce_strings);
+    field
+}
+
+fn stored_type(schema_type: &SchemaType, coerce_strings: bool) -> SchemaType {
+    match schema_type {
+        SchemaType::NumericString | SchemaType::WideNumericString if !coerce_strings => {
+            SchemaType::String
+        }
+        SchemaType::NumericString => SchemaType::Integer,
+        SchemaType::WideNumericString => SchemaType::Int64,
+        SchemaType::Optional(inner) => {
+            SchemaType::Optional(Box::new(stored_type(inner, coerce_strings)))
+        }
+        SchemaType::Array(inner) => SchemaType::Array(Box::new(stored_type(inner, coerce_strings))),
+        other => other.clone(),
+    }
+}
 
 pub fn generate_prisma_type(schema_type: &SchemaType) -> Option<String> {
     match schema_type {
         SchemaType::String => Some("String".to_string()),
         SchemaType::Number => Some("Float".to_string()),
         SchemaType::Integer => Some("Int".to_string()),
+        SchemaType::Int64 | SchemaType::BigInt => Some("BigInt".to_string()),
+        SchemaType::Custom(format) => Some(format.prisma_type.clone()),
+        SchemaType::NumericString => Some("Int".to_string()),
+        SchemaType::WideNumericString => Some("BigInt".to_string()),
+        SchemaType::Decimal | SchemaType::DecimalString => Some("Decimal".to_string()),
+        SchemaType::BooleanString => Some("Boolean".to_string()),
         SchemaType::Boolean => Some("Boolean".to_string()),
         SchemaType::DateTime => Some("DateTime".to_string()),
+        SchemaType::EpochSeconds | SchemaType::EpochMillis => Some("DateTime".to_string()),
         SchemaType::Date => Some("DateTime".to_string()),
         SchemaType::Uuid => Some("String".to_string()),
         SchemaType::Email => Some("String".to_string()),
         SchemaType::Url => Some("String".to_string()),
+        SchemaType::Ipv4
+        | SchemaType::Ipv6
+        | SchemaType::Ulid
+        | SchemaType::Cuid
+        | SchemaType::Time
+        | SchemaType::Duration
+        | SchemaType::HexColor
+        | SchemaType::Semver
+        | SchemaType::Base64
+        | SchemaType::Jwt
+        | SchemaType::Phone => Some("String".to_string()),
         SchemaType::Enum(_) => Some("String".to_string()),
         SchemaType::Array(inner) => match inner.as_ref() {
             SchemaType::String => Some("String[]".to_string()),
             SchemaType::Integer => Some("Int[]".to_string()),
+            SchemaType::Int64 | SchemaType::BigInt => Some("BigInt[]".to_string()),
+            SchemaType::Custom(format) => Some(format!("{}[]", format.prisma_type)),
+            SchemaType::NumericString => Some("Int[]".to_string()),
+            SchemaType::WideNumericString => Some("BigInt[]".to_string()),
+            SchemaType::Decimal | SchemaType::DecimalString => Some("Decimal[]".to_string()),
+            SchemaType::BooleanString => Some("Boolean[]".to_string()),
             SchemaType::Number => Some("Float[]".to_string()),
             SchemaType::Boolean => Some("Boolean[]".to_string()),
-            SchemaType::DateTime | SchemaType::Date => Some("DateTime[]".to_string()),
-            SchemaType::Uuid | SchemaType::Email | SchemaType::Url => Some("String[]".to_string()),
+            SchemaType::DateTime
+            | SchemaType::Date
+            | SchemaType::EpochSeconds
+            | SchemaType::EpochMillis => Some("DateTime[]".to_string()),
+            SchemaType::Uuid
+            | SchemaType::Email
+            | SchemaType::Url
+            | SchemaType::Ipv4
+            | SchemaType::Ipv6
+            | SchemaType::Ulid
+            | SchemaType::Cuid
+            | SchemaType::Time
+            | SchemaType::Duration
+            | SchemaType::HexColor
+            | SchemaType::Semver
+            | SchemaType::Base64
+            | SchemaType::Jwt
+            | SchemaType::Phone => Some("String[]".to_string()),
             _ => Some("Json".to_string()),
         },
         SchemaType::Null => None,
         SchemaType::Optional(inner) => generate_prisma_type(inner).map(|t| format!("{}?", t)),
-        SchemaType::Any | SchemaType::Union(_) => Some("Json".to_string()),
-        SchemaType::Object(_) => None,
+        SchemaType::Any
+        | SchemaType::Union(_)
+        | SchemaType::DiscriminatedUnion { .. }
+        | SchemaType::Map(_)
+        | SchemaType::Tuple(_) => Some("Json".to_string()),
+        SchemaType::Object(_) | SchemaType::Ref(_) => None,
     }
 }
@@ -1,11 +1,12 @@
 mod types;
 
 use crate::generators::{Generator, GeneratorConfig};
-use crate::types::{NestedType, ObjectType, Schema, SchemaType};
+use crate::types::{NestedType, ObjectType, Schema};
+use crate::utils::quote_string;
 use anyhow::Result;
 use std::collections::HashMap;
 
-use types::{format_field_name, generate_type};
+use types::{doc_comment, field_doc, format_field_name, generate_field_type, generate_type};
 
 pub struct TypeScriptGenerator;
 
@@ -14,7 +15,7 @@ impl TypeScriptGenerator {
         Self
     }
 
-    fn generate_interface(
+    pub(crate) fn generate_interface(
         &self,
         name: &str,
         obj: &ObjectType,
@@ -31,13 +32,14 @@ impl TypeScriptGenerator {
 
         for field in &obj.fields {
             let field_name = format_field_name(&field.name);
-            let field_type = generate_type(&field.field_type, indent, type_refs);
+            let field_type = generate_field_type(field, indent, type_refs, config);
             let optional = if field.optional { "?" } else { "" };
             let readonly = if config.ts_readonly_fields || field.metadata.is_readonly {
                 "readonly "
             } else {
                 ""
             };
+            lines.extend(doc_comment(&field_doc(field), indent));
             lines.push(format!(
                 "{}{}{}{}: {};",
                 indent, readonly, field_name, optional, field_type
@@ -74,6 +76,26 @@ impl Generator for TypeScriptGenerator {
             self.build_type_refs(&schema.nested_types)
         };
 
+        let export_keyword = if config.ts_export_interfaces {
+            "export "
+        } else {
+            ""
+        };
+
+        // User-defined formats become branded strings
+        let formats = schema.custom_formats();
+        for format in &formats {
+            output.push(format!(
+                "{}type {} = string & {{ readonly __brand: {} }};",
+                export_keyword,
+                format.name,
+                quote_string(&format.name)
+            ));
+        }
+        if !formats.is_empty() {
+            output.push(String::new());
+        }
+
         if !config.flat_mode && !schema.nested_types.is_empty() {
             for nt in schema.nested_types.iter().rev() {
                 output.push(self.generate_interface(
@@ -87,14 +109,31 @@ impl Generator for TypeScriptGenerator {
             }
         }
 
-        if let SchemaType::Object(obj) = &schema.root_type {
+        if let Some(obj) = schema.root_object() {
             output.push(self.generate_interface(
-                &schema.name,
+                &schema.root_object_name(),
                 obj,
                 &config.indent,
                 &type_refs,
                 config,
             ));
+        } else if let Some(union) = schema.root_union() {
+            output.push(format!(
+                "{}type {} = {};",
+                export_keyword,
+                schema.root_object_name(),
+                generate_type(union, &config.indent, &type_refs, config)
+            ));
+        }
+
+        if schema.is_array_root() {
+            output.push(String::new());
+            output.push(format!(
+                "{}type {} = {}[];",
+                export_keyword,
+                schema.name,
+                schema.root_object_name()
+            ));
         }
 
         Ok(output.join("\n"))
@@ -108,7 +147,7 @@ impl Generator for TypeScriptGenerator {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::Field;
+    use crate::types::{Constraints, Field, FieldMetadata, SchemaType};
 
     #[test]
     fn test_generate_simple_interface() {
@@ -179,8 +218,10 @@ mod tests {
         let gen = TypeScriptGenerator::new();
         let obj = ObjectType::new(vec![Field::new("name", SchemaType::String)]);
         let schema = Schema::new("User", SchemaType::Object(obj));
-        let mut config = GeneratorConfig::default();
-        config.ts_export_interfaces = true;
+        let config = GeneratorConfig {
+            ts_export_interfaces: true,
+            ..Default::default()
+        };
 
         let output = gen.generate(&schema, &config).unwrap();
 
@@ -208,8 +249,10 @@ mod tests {
             Field::new("name", SchemaType::String),
         ]);
         let schema = Schema::new("User", SchemaType::Object(obj));
-        let mut config = GeneratorConfig::default();
-        config.ts_readonly_fields = true;
+        let config = GeneratorConfig {
+            ts_readonly_fields: true,
+            ..Default::default()
+        };
 
         let output = gen.generate(&schema, &config).unwrap();
 
@@ -307,4 +350,198 @@ mod tests {
 
         assert!(output.starts_with("// Generated by mold"));
     }
+
+    #[test]
+    fn test_nullable_field() {
+        let gen = TypeScriptGenerator::new();
+        let obj = ObjectType::new(vec![
+            Field::new("bio", SchemaType::String).nullable(),
+            Field::new("nickname", SchemaType::String).optional().nullable(),
+        ]);
+        let schema = Schema::new("User", SchemaType::Object(obj));
+        let config = GeneratorConfig::default();
+
+        let output = gen.generate(&schema, &config).unwrap();
+
+        assert!(output.contains("bio: string | null;"));
+        assert!(output.contains("nickname?: string | null;"));
+    }
+
+    #[test]
+    fn test_array_root_emits_collection_alias() {
+        let gen = TypeScriptGenerator::new();
+        let obj = ObjectType::new(vec![Field::new("id", SchemaType::Integer)]);
+        let schema = Schema::new(
+            "Users",
+            SchemaType::Array(Box::new(SchemaType::Object(obj))),
+        );
+        let config = GeneratorConfig {
+            ts_export_interfaces: true,
+            ..Default::default()
+        };
+
+        let output = gen.generate(&schema, &config).unwrap();
+
+        assert!(output.contains("export interface User {"));
+        assert!(output.contains("export type Users = User[];"));
+    }
+
+    #[test]
+    fn test_recursive_reference() {
+        let gen = TypeScriptGenerator::new();
+        let obj = ObjectType::new(vec![
+            Field::new("name", SchemaType::String),
+            Field::new(
+                "children",
+                SchemaType::Array(Box::new(SchemaType::Ref("Category".to_string()))),
+            ),
+        ]);
+        let schema = Schema::new("Category", SchemaType::Object(obj));
+        let config = GeneratorConfig::default();
+
+        let output = gen.generate(&schema, &config).unwrap();
+
+        assert!(output.contains("children: Category[];"));
+    }
+
+    #[test]
+    fn test_map_type() {
+        let gen = TypeScriptGenerator::new();
+        let obj = ObjectType::new(vec![Field::new(
+            "scores",
+            SchemaType::Map(Box::new(SchemaType::Integer)),
+        )]);
+        let schema = Schema::new("Test", SchemaType::Object(obj));
+        let config = GeneratorConfig::default();
+
+        let output = gen.generate(&schema, &config).unwrap();
+
+        assert!(output.contains("scores: Record<string, number>;"));
+    }
+
+    #[test]
+    fn test_tuple_type() {
+        let gen = TypeScriptGenerator::new();
+        let obj = ObjectType::new(vec![Field::new(
+            "entry",
+            SchemaType::Tuple(vec![SchemaType::String, SchemaType::Integer]),
+        )]);
+        let schema = Schema::new("Test", SchemaType::Object(obj));
+        let config = GeneratorConfig::default();
+
+        let output = gen.generate(&schema, &config).unwrap();
+
+        assert!(output.contains("entry: [string, number];"));
+    }
+
+    fn click_and_view() -> (ObjectType, ObjectType) {
+        let click = ObjectType::new(vec![
+            Field::new("type", SchemaType::Enum(vec!["click".to_string()])),
+            Field::new("x", SchemaType::Integer),
+        ]);
+        let view = ObjectType::new(vec![
+            Field::new("page", SchemaType::String),
+            Field::new("type", SchemaType::Enum(vec!["view".to_string()])),
+        ]);
+        (click, view)
+    }
+
+    #[test]
+    fn test_discriminated_union_root() {
+        let gen = TypeScriptGenerator::new();
+        let (click, view) = click_and_view();
+        let root = SchemaType::DiscriminatedUnion {
+            tag: "type".to_string(),
+            variants: vec![click.clone(), view.clone()],
+        };
+        let schema = Schema::new("Events", SchemaType::Array(Box::new(root))).with_nested_types(
+            vec![
+                NestedType::new("EventClick", click),
+                NestedType::new("EventView", view),
+            ],
+        );
+        let config = GeneratorConfig::default();
+
+        let output = gen.generate(&schema, &config).unwrap();
+
+        assert!(output.contains("interface EventClick {"));
+        assert!(output.contains("\"type\": \"click\";"));
+        assert!(output.contains("type Event = EventClick | EventView;"));
+        assert!(output.contains("type Events = Event[];"));
+    }
+
+    #[test]
+    fn test_custom_format_is_branded() {
+        let gen = TypeScriptGenerator::new();
+        let format = crate::types::CustomFormat {
+            name: "Sku".to_string(),
+            pattern: "^SKU-[0-9]+$".to_string(),
+            prisma_type: "String".to_string(),
+            prisma_attribute: None,
+        };
+        let obj = ObjectType::new(vec![Field::new(
+            "sku",
+            SchemaType::Custom(Box::new(format)),
+        )]);
+        let schema = Schema::new("Product", SchemaType::Object(obj));
+        let config = GeneratorConfig::default();
+
+        let output = gen.generate(&schema, &config).unwrap();
+
+        assert!(output.contains("type Sku = string & { readonly __brand: \"Sku\" };"));
+        assert!(output.contains("sku: Sku;"));
+    }
+
+    #[test]
+    fn test_numeric_strings_stay_strings_unless_coerced() {
+        let gen = TypeScriptGenerator::new();
+        let obj = ObjectType::new(vec![Field::new("price", SchemaType::DecimalString)]);
+        let schema = Schema::new("Test", SchemaType::Object(obj));
+
+        let output = gen.generate(&schema, &GeneratorConfig::default()).unwrap();
+        assert!(output.contains("price: string;"));
+
+        let config = GeneratorConfig {
+            coerce_strings: true,
+            ..Default::default()
+        };
+        let output = gen.generate(&schema, &config).unwrap();
+        assert!(output.contains("price: number;"));
+    }
+
+    #[test]
+    fn test_epoch_fields_document_their_unit() {
+        let gen = TypeScriptGenerator::new();
+        let obj = ObjectType::new(vec![
+            Field::new("createdAt", SchemaType::EpochSeconds),
+            Field::new("seenAt", SchemaType::Array(Box::new(SchemaType::EpochMillis))),
+        ]);
+        let schema = Schema::new("Test", SchemaType::Object(obj));
+
+        let output = gen.generate(&schema, &GeneratorConfig::default()).unwrap();
+
+        assert!(output.contains("  /** Unix timestamp in seconds */\n  createdAt: number;"));
+        assert!(output.contains("  /** Unix timestamp in milliseconds */\n  seenAt: number[];"));
+    }
+
+    #[test]
+    fn test_constraints_become_jsdoc_tags() {
+        let gen = TypeScriptGenerator::new();
+        let metadata = FieldMetadata {
+            constraints: Constraints {
+                min_length: Some(1),
+                max_length: Some(40),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let obj = ObjectType::new(vec![Field::new("name", SchemaType::String).with_metadata(metadata)]);
+        let schema = Schema::new("Test", SchemaType::Object(obj));
+
+        let output = gen.generate(&schema, &GeneratorConfig::default()).unwrap();
+
+        assert!(
+            output.contains("  /**\n   * @minLength 1\n   * @maxLength 40\n   */\n  name: string;")
+        );
+    }
 }
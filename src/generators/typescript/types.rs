@@ -1,15 +1,34 @@
-use crate::types::{ObjectType, SchemaType};
-use crate::utils::sanitize_identifier;
+use crate::generators::GeneratorConfig;
+use crate::types::{Field, ObjectType, SchemaType};
+use crate::utils::{comment_text, quote_string, sanitize_identifier};
 use std::collections::HashMap;
 
 pub fn generate_type(
     schema_type: &SchemaType,
     indent: &str,
     type_refs: &HashMap<String, String>,
+    config: &GeneratorConfig,
 ) -> String {
     match schema_type {
         SchemaType::String => "string".to_string(),
-        SchemaType::Number | SchemaType::Integer => "number".to_string(),
+        SchemaType::Number
+        | SchemaType::Integer
+        | SchemaType::Int64
+        | SchemaType::Decimal
+        | SchemaType::EpochSeconds
+        | SchemaType::EpochMillis => "number".to_string(),
+        SchemaType::BigInt => "bigint".to_string(),
+        SchemaType::Custom(format) => format.name.clone(),
+        SchemaType::NumericString | SchemaType::WideNumericString | SchemaType::DecimalString
+            if config.coerce_strings =>
+        {
+            "number".to_string()
+        }
+        SchemaType::BooleanString if config.coerce_strings => "boolean".to_string(),
+        SchemaType::NumericString
+        | SchemaType::WideNumericString
+        | SchemaType::DecimalString
+        | SchemaType::BooleanString => "string".to_string(),
         SchemaType::Boolean => "boolean".to_string(),
         SchemaType::Null => "null".to_string(),
         SchemaType::Any => "unknown".to_string(),
@@ -18,29 +37,50 @@ pub fn generate_type(
         SchemaType::Uuid => "string".to_string(),
         SchemaType::Email => "string".to_string(),
         SchemaType::Url => "string".to_string(),
+        SchemaType::Ipv4
+        | SchemaType::Ipv6
+        | SchemaType::Ulid
+        | SchemaType::Cuid
+        | SchemaType::Time
+        | SchemaType::Duration
+        | SchemaType::HexColor
+        | SchemaType::Semver
+        | SchemaType::Base64
+        | SchemaType::Jwt
+        | SchemaType::Phone => "string".to_string(),
         SchemaType::Enum(values) => {
             values
                 .iter()
-                .map(|v| format!("\"{}\"", v))
+                .map(|v| quote_string(v))
                 .collect::<Vec<_>>()
                 .join(" | ")
         }
         SchemaType::Array(inner) => {
-            let inner_type = generate_type(inner, indent, type_refs);
-            if matches!(**inner, SchemaType::Union(_) | SchemaType::Enum(_)) {
+            let inner_type = generate_type(inner, indent, type_refs, config);
+            if matches!(
+                **inner,
+                SchemaType::Union(_) | SchemaType::Enum(_) | SchemaType::DiscriminatedUnion { .. }
+            ) {
                 format!("({})[]", inner_type)
             } else {
                 format!("{}[]", inner_type)
             }
         }
+        SchemaType::Tuple(types) => {
+            let type_strings: Vec<String> = types
+                .iter()
+                .map(|t| generate_type(t, indent, type_refs, config))
+                .collect();
+            format!("[{}]", type_strings.join(", "))
+        }
         SchemaType::Optional(inner) => {
-            let inner_type = generate_type(inner, indent, type_refs);
+            let inner_type = generate_type(inner, indent, type_refs, config);
             format!("{} | undefined", inner_type)
         }
         SchemaType::Union(types) => {
             let mut type_strings: Vec<String> = types
                 .iter()
-                .map(|t| generate_type(t, indent, type_refs))
+                .map(|t| generate_type(t, indent, type_refs, config))
                 .collect();
             type_strings.sort();
             type_strings.dedup();
@@ -50,21 +90,103 @@ pub fn generate_type(
                 type_strings.join(" | ")
             }
         }
+        SchemaType::DiscriminatedUnion { variants, .. } => variants
+            .iter()
+            .map(|v| generate_type(&SchemaType::Object(v.clone()), indent, type_refs, config))
+            .collect::<Vec<_>>()
+            .join(" | "),
+        SchemaType::Map(inner) => {
+            format!("Record<string, {}>", generate_type(inner, indent, type_refs, config))
+        }
+        SchemaType::Ref(name) => name.clone(),
         SchemaType::Object(obj) => {
             let obj_key = format!("{:?}", obj);
             if let Some(type_name) = type_refs.get(&obj_key) {
                 type_name.clone()
             } else {
-                generate_inline_object(obj, indent, type_refs)
+                generate_inline_object(obj, indent, type_refs, config)
             }
         }
     }
 }
 
+/// Render a field's type, appending `| null` for nullable fields
+pub fn generate_field_type(
+    field: &Field,
+    indent: &str,
+    type_refs: &HashMap<String, String>,
+    config: &GeneratorConfig,
+) -> String {
+    let field_type = generate_type(&field.field_type, indent, type_refs, config);
+    if field.nullable {
+        format!("{} | null", field_type)
+    } else {
+        field_type
+    }
+}
+
+/// Doc comment lines for a field: what its type means (the unit of a Unix
+/// timestamp, the model it references) followed by JSDoc constraint tags
+pub fn field_doc(field: &Field) -> Vec<String> {
+    let mut schema_type = &field.field_type;
+    while let SchemaType::Array(inner) | SchemaType::Optional(inner) = schema_type {
+        schema_type = inner;
+    }
+    let note = match schema_type {
+        SchemaType::EpochSeconds => Some("Unix timestamp in seconds".to_string()),
+        SchemaType::EpochMillis => Some("Unix timestamp in milliseconds".to_string()),
+        _ => field
+            .metadata
+            .references
+            .as_ref()
+            .map(|model| format!("References `{}`", model)),
+    };
+    let mut doc: Vec<String> = field
+        .metadata
+        .description
+        .as_deref()
+        .map(comment_text)
+        .into_iter()
+        .chain(note)
+        .collect();
+
+    let c = &field.metadata.constraints;
+    let tags = [
+        ("@default", field.metadata.default_value.clone()),
+        ("@minimum", c.minimum.map(|n| n.to_string())),
+        ("@maximum", c.maximum.map(|n| n.to_string())),
+        ("@minLength", c.min_length.map(|n| n.to_string())),
+        ("@maxLength", c.max_length.map(|n| n.to_string())),
+        ("@minItems", c.min_items.map(|n| n.to_string())),
+        ("@maxItems", c.max_items.map(|n| n.to_string())),
+    ];
+    for (tag, value) in tags {
+        if let Some(value) = value {
+            doc.push(format!("{} {}", tag, value));
+        }
+    }
+    doc
+}
+
+/// Render doc lines as a `/** ... */` comment, on one line when there is
+/// only one. A `*/` in the text would end the comment, so it becomes `*\/`.
+pub fn doc_comment(doc: &[String], indent: &str) -> Vec<String> {
+    let doc: Vec<String> = doc.iter().map(|line| line.replace("*/", "*\\/")).collect();
+    match doc.as_slice() {
+        [] => Vec::new(),
+        [line] => vec![format!("{}/** {} */", indent, line)],
+        lines => std::iter::once(format!("{}/**", indent))
+            .chain(lines.iter().map(|line| format!("{} * {}", indent, line)))
+            .chain(std::iter::once(format!("{} */", indent)))
+            .collect(),
+    }
+}
+
 pub fn generate_inline_object(
     obj: &ObjectType,
     indent: &str,
     type_refs: &HashMap<String, String>,
+    config: &GeneratorConfig,
 ) -> String {
     if obj.fields.is_empty() {
         return "Record<string, unknown>".to_string();
@@ -73,8 +195,9 @@ pub fn generate_inline_object(
     let mut lines = vec!["{".to_string()];
     for field in &obj.fields {
         let field_name = format_field_name(&field.name);
-        let field_type = generate_type(&field.field_type, &format!("{}  ", indent), type_refs);
+        let field_type = generate_field_type(field, &format!("{}  ", indent), type_refs, config);
         let optional = if field.optional { "?" } else { "" };
+        lines.extend(doc_comment(&field_doc(field), &format!("{}  ", indent)));
         lines.push(format!(
             "{}  {}{}: {};",
             indent, field_name, optional, field_type
@@ -1,11 +1,11 @@
 mod types;
 
-use crate::generators::{Generator, GeneratorConfig};
-use crate::types::{NestedType, ObjectType, Schema, SchemaType};
+use crate::generators::{Generator, GeneratorConfig, TypeScriptGenerator};
+use crate::types::{NestedType, ObjectType, Schema};
 use anyhow::Result;
 use std::collections::HashMap;
 
-use types::{format_field_name, generate_type};
+use types::{format_field_name, generate_field_type, generate_type};
 
 pub struct ZodGenerator;
 
@@ -20,22 +20,34 @@ impl ZodGenerator {
         obj: &ObjectType,
         indent: &str,
         type_refs: &HashMap<String, String>,
-        strict_mode: bool,
+        config: &GeneratorConfig,
+        recursive: bool,
     ) -> String {
         let schema_name = format!("{}Schema", name);
         let inner_indent = format!("{}  ", indent);
-        let mut lines = vec![format!("const {} = z.object({{", schema_name)];
+        // Recursive schemas can't infer their own type, so they are annotated
+        // with a hand-written interface instead
+        let annotation = if recursive {
+            format!(": z.ZodType<{}>", name)
+        } else {
+            String::new()
+        };
+        let mut lines = vec![format!(
+            "const {}{} = z.object({{",
+            schema_name, annotation
+        )];
 
         for field in &obj.fields {
             let field_name = format_field_name(&field.name);
-            let mut field_type = generate_type(&field.field_type, &inner_indent, type_refs);
-            if field.optional {
-                field_type = format!("{}.optional()", field_type);
-            }
+            let field_type = generate_field_type(field, &inner_indent, type_refs, config);
             lines.push(format!("{}{}: {},", inner_indent, field_name, field_type));
         }
 
-        let strict_suffix = if strict_mode { ".strict()" } else { "" };
+        let strict_suffix = if config.zod_strict_objects {
+            ".strict()"
+        } else {
+            ""
+        };
         lines.push(format!("}}){};\n", strict_suffix));
         lines.join("\n")
     }
@@ -50,6 +62,17 @@ impl ZodGenerator {
     }
 }
 
+fn find_object<'a>(schema: &'a Schema, name: &str) -> Option<&'a ObjectType> {
+    if schema.root_object_name() == name {
+        return schema.root_object();
+    }
+    schema
+        .nested_types
+        .iter()
+        .find(|nt| nt.name == name)
+        .map(|nt| &nt.object)
+}
+
 impl Default for ZodGenerator {
     fn default() -> Self {
         Self::new()
@@ -71,6 +94,7 @@ impl Generator for ZodGenerator {
         };
 
         let mut all_type_names: Vec<String> = Vec::new();
+        let recursive = schema.recursive_type_names();
 
         if !config.flat_mode && !schema.nested_types.is_empty() {
             for nt in schema.nested_types.iter().rev() {
@@ -79,24 +103,61 @@ impl Generator for ZodGenerator {
                     &nt.object,
                     &config.indent,
                     &type_refs,
-                    config.zod_strict_objects,
+                    config,
+                    recursive.contains(&nt.name),
                 ));
                 all_type_names.push(nt.name.clone());
             }
         }
 
-        if let SchemaType::Object(obj) = &schema.root_type {
+        if let Some(obj) = schema.root_object() {
+            let root_name = schema.root_object_name();
             output.push(self.generate_schema(
-                &schema.name,
+                &root_name,
                 obj,
                 &config.indent,
                 &type_refs,
-                config.zod_strict_objects,
+                config,
+                recursive.contains(&root_name),
+            ));
+            all_type_names.push(root_name);
+        } else if let Some(union) = schema.root_union() {
+            let root_name = schema.root_object_name();
+            output.push(format!(
+                "const {}Schema = {};\n",
+                root_name,
+                generate_type(union, &config.indent, &type_refs, config)
+            ));
+            all_type_names.push(root_name);
+        }
+
+        if schema.is_array_root() {
+            output.push(format!(
+                "const {}Schema = z.array({}Schema);\n",
+                schema.name,
+                schema.root_object_name()
             ));
             all_type_names.push(schema.name.clone());
         }
 
+        let ts_config = GeneratorConfig {
+            ts_export_interfaces: false,
+            ts_readonly_fields: false,
+            ..config.clone()
+        };
         for name in &all_type_names {
+            if recursive.contains(name) {
+                if let Some(obj) = find_object(schema, name) {
+                    output.push(TypeScriptGenerator::new().generate_interface(
+                        name,
+                        obj,
+                        &config.indent,
+                        &type_refs,
+                        &ts_config,
+                    ));
+                    continue;
+                }
+            }
             output.push(format!(
                 "type {} = z.infer<typeof {}Schema>;",
                 name, name
@@ -121,7 +182,7 @@ impl Generator for ZodGenerator {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::Field;
+    use crate::types::{Constraints, CustomFormat, Field, FieldMetadata, SchemaType};
 
     #[test]
     fn test_generate_simple_schema() {
@@ -195,8 +256,10 @@ mod tests {
         let gen = ZodGenerator::new();
         let obj = ObjectType::new(vec![Field::new("name", SchemaType::String)]);
         let schema = Schema::new("User", SchemaType::Object(obj));
-        let mut config = GeneratorConfig::default();
-        config.zod_strict_objects = true;
+        let config = GeneratorConfig {
+            zod_strict_objects: true,
+            ..Default::default()
+        };
 
         let output = gen.generate(&schema, &config).unwrap();
 
@@ -307,4 +370,225 @@ mod tests {
 
         assert!(output.contains("import { z } from \"zod\""));
     }
+
+    #[test]
+    fn test_nullable_and_nullish_fields() {
+        let gen = ZodGenerator::new();
+        let obj = ObjectType::new(vec![
+            Field::new("bio", SchemaType::String).nullable(),
+            Field::new("nickname", SchemaType::String).optional().nullable(),
+        ]);
+        let schema = Schema::new("User", SchemaType::Object(obj));
+        let config = GeneratorConfig::default();
+
+        let output = gen.generate(&schema, &config).unwrap();
+
+        assert!(output.contains("bio: z.string().nullable(),"));
+        assert!(output.contains("nickname: z.string().nullish(),"));
+    }
+
+    #[test]
+    fn test_union_with_null_becomes_nullable() {
+        let gen = ZodGenerator::new();
+        let obj = ObjectType::new(vec![Field::new(
+            "scores",
+            SchemaType::Array(Box::new(SchemaType::Union(vec![
+                SchemaType::Integer,
+                SchemaType::Null,
+            ]))),
+        )]);
+        let schema = Schema::new("Test", SchemaType::Object(obj));
+        let config = GeneratorConfig::default();
+
+        let output = gen.generate(&schema, &config).unwrap();
+
+        assert!(output.contains("scores: z.array(z.number().int().nullable()),"));
+    }
+
+    #[test]
+    fn test_array_root_emits_array_schema() {
+        let gen = ZodGenerator::new();
+        let obj = ObjectType::new(vec![Field::new("id", SchemaType::Integer)]);
+        let schema = Schema::new(
+            "Users",
+            SchemaType::Array(Box::new(SchemaType::Object(obj))),
+        );
+        let config = GeneratorConfig::default();
+
+        let output = gen.generate(&schema, &config).unwrap();
+
+        assert!(output.contains("const UserSchema = z.object({"));
+        assert!(output.contains("const UsersSchema = z.array(UserSchema);"));
+        assert!(output.contains("type Users = z.infer<typeof UsersSchema>;"));
+        assert!(output.contains("export { UserSchema, UsersSchema };"));
+    }
+
+    #[test]
+    fn test_recursive_schema_is_lazy_and_annotated() {
+        let gen = ZodGenerator::new();
+        let obj = ObjectType::new(vec![
+            Field::new("text", SchemaType::String),
+            Field::new(
+                "replies",
+                SchemaType::Array(Box::new(SchemaType::Ref("Comment".to_string()))),
+            ),
+        ]);
+        let schema = Schema::new("Comment", SchemaType::Object(obj));
+        let config = GeneratorConfig::default();
+
+        let output = gen.generate(&schema, &config).unwrap();
+
+        assert!(output.contains("const CommentSchema: z.ZodType<Comment> = z.object({"));
+        assert!(output.contains("replies: z.array(z.lazy(() => CommentSchema)),"));
+        assert!(output.contains("interface Comment {"));
+        assert!(output.contains("  replies: Comment[];"));
+        assert!(!output.contains("z.infer<typeof CommentSchema>"));
+    }
+
+    #[test]
+    fn test_map_schema() {
+        let gen = ZodGenerator::new();
+        let value = ObjectType::new(vec![Field::new("name", SchemaType::String)]);
+        let obj = ObjectType::new(vec![Field::new(
+            "users",
+            SchemaType::Map(Box::new(SchemaType::Object(value.clone()))),
+        )]);
+        let schema = Schema::new("Test", SchemaType::Object(obj))
+            .with_nested_types(vec![NestedType::new("TestUsersValue", value)]);
+        let config = GeneratorConfig::default();
+
+        let output = gen.generate(&schema, &config).unwrap();
+
+        assert!(output.contains("users: z.record(z.string(), TestUsersValueSchema),"));
+    }
+
+    #[test]
+    fn test_tuple_schema() {
+        let gen = ZodGenerator::new();
+        let obj = ObjectType::new(vec![Field::new(
+            "entry",
+            SchemaType::Tuple(vec![SchemaType::String, SchemaType::Integer]),
+        )]);
+        let schema = Schema::new("Test", SchemaType::Object(obj));
+        let config = GeneratorConfig::default();
+
+        let output = gen.generate(&schema, &config).unwrap();
+
+        assert!(output.contains("entry: z.tuple([z.string(), z.number().int()]),"));
+    }
+
+    fn click_and_view() -> (ObjectType, ObjectType) {
+        let click = ObjectType::new(vec![
+            Field::new("type", SchemaType::Enum(vec!["click".to_string()])),
+            Field::new("x", SchemaType::Integer),
+        ]);
+        let view = ObjectType::new(vec![
+            Field::new("page", SchemaType::String),
+            Field::new("type", SchemaType::Enum(vec!["view".to_string()])),
+        ]);
+        (click, view)
+    }
+
+    #[test]
+    fn test_discriminated_union_field() {
+        let gen = ZodGenerator::new();
+        let (click, view) = click_and_view();
+        let obj = ObjectType::new(vec![Field::new(
+            "event",
+            SchemaType::DiscriminatedUnion {
+                tag: "type".to_string(),
+                variants: vec![click.clone(), view.clone()],
+            },
+        )]);
+        let schema = Schema::new("Log", SchemaType::Object(obj)).with_nested_types(vec![
+            NestedType::new("LogEventClick", click),
+            NestedType::new("LogEventView", view),
+        ]);
+        let config = GeneratorConfig::default();
+
+        let output = gen.generate(&schema, &config).unwrap();
+
+        assert!(output.contains("type: z.literal(\"click\"),"));
+        assert!(output.contains(
+            "event: z.discriminatedUnion(\"type\", [LogEventClickSchema, LogEventViewSchema]),"
+        ));
+    }
+
+    #[test]
+    fn test_custom_pattern_slashes_are_escaped_once() {
+        let gen = ZodGenerator::new();
+        let format = CustomFormat {
+            name: "Path".to_string(),
+            pattern: r"^/api\/v\d+/[a-z]+\\/$".to_string(),
+            prisma_type: "String".to_string(),
+            prisma_attribute: None,
+        };
+        let obj = ObjectType::new(vec![Field::new(
+            "path",
+            SchemaType::Custom(Box::new(format)),
+        )]);
+        let schema = Schema::new("Route", SchemaType::Object(obj));
+
+        let output = gen.generate(&schema, &GeneratorConfig::default()).unwrap();
+        assert!(output.contains(r"path: z.string().regex(/^\/api\/v\d+\/[a-z]+\\\/$/),"));
+    }
+
+    #[test]
+    fn test_numeric_strings_coerce_only_when_asked() {
+        let gen = ZodGenerator::new();
+        let obj = ObjectType::new(vec![
+            Field::new("count", SchemaType::NumericString),
+            Field::new("enabled", SchemaType::BooleanString),
+        ]);
+        let schema = Schema::new("Test", SchemaType::Object(obj));
+
+        let output = gen.generate(&schema, &GeneratorConfig::default()).unwrap();
+        assert!(output.contains(r"count: z.string().regex(/^-?\d+$/),"));
+        assert!(output.contains("enabled: z.enum([\"true\", \"false\"]),"));
+
+        let config = GeneratorConfig {
+            coerce_strings: true,
+            ..Default::default()
+        };
+        let output = gen.generate(&schema, &config).unwrap();
+        assert!(output.contains("count: z.coerce.number().int(),"));
+        assert!(output.contains(
+            "enabled: z.enum([\"true\", \"false\"]).transform((v) => v === \"true\"),"
+        ));
+    }
+
+    #[test]
+    fn test_generate_constraint_refinements() {
+        let gen = ZodGenerator::new();
+        let bounded = |constraints: Constraints| FieldMetadata {
+            constraints,
+            ..Default::default()
+        };
+        let obj = ObjectType::new(vec![
+            Field::new("age", SchemaType::Integer).with_metadata(bounded(Constraints {
+                minimum: Some(0.0),
+                maximum: Some(120.0),
+                ..Default::default()
+            })),
+            Field::new("code", SchemaType::String).with_metadata(bounded(Constraints {
+                min_length: Some(2),
+                max_length: Some(2),
+                ..Default::default()
+            })),
+            Field::new("tags", SchemaType::Array(Box::new(SchemaType::String)))
+                .optional()
+                .with_metadata(bounded(Constraints {
+                    min_items: Some(1),
+                    max_items: Some(5),
+                    ..Default::default()
+                })),
+        ]);
+        let schema = Schema::new("Test", SchemaType::Object(obj));
+
+        let output = gen.generate(&schema, &GeneratorConfig::default()).unwrap();
+
+        assert!(output.contains("age: z.number().int().min(0).max(120),"));
+        assert!(output.contains("code: z.string().length(2),"));
+        assert!(output.contains("tags: z.array(z.string()).nonempty().max(5).optional(),"));
+    }
 }
@@ -1,16 +1,24 @@
-use crate::types::{ObjectType, SchemaType};
-use crate::utils::sanitize_identifier;
+use crate::generators::GeneratorConfig;
+use crate::types::{Field, ObjectType, SchemaType};
+use crate::utils::{default_literal, quote_string, sanitize_identifier};
 use std::collections::HashMap;
 
 pub fn generate_type(
     schema_type: &SchemaType,
     indent: &str,
     type_refs: &HashMap<String, String>,
+    config: &GeneratorConfig,
 ) -> String {
     match schema_type {
         SchemaType::String => "z.string()".to_string(),
-        SchemaType::Number => "z.number()".to_string(),
-        SchemaType::Integer => "z.number().int()".to_string(),
+        SchemaType::Number | SchemaType::Decimal => "z.number()".to_string(),
+        SchemaType::Integer | SchemaType::Int64 => "z.number().int()".to_string(),
+        SchemaType::EpochSeconds => {
+            "z.number().int().transform((s) => new Date(s * 1000))".to_string()
+        }
+        SchemaType::EpochMillis => "z.number().int().transform((ms) => new Date(ms))".to_string(),
+        // JSON numbers past 2^53 arrive rounded, so accept strings as well
+        SchemaType::BigInt => "z.coerce.bigint()".to_string(),
         SchemaType::Boolean => "z.boolean()".to_string(),
         SchemaType::Null => "z.null()".to_string(),
         SchemaType::Any => "z.unknown()".to_string(),
@@ -19,50 +27,194 @@ pub fn generate_type(
         SchemaType::Uuid => "z.string().uuid()".to_string(),
         SchemaType::Email => "z.string().email()".to_string(),
         SchemaType::Url => "z.string().url()".to_string(),
+        SchemaType::Ipv4 => "z.string().ip({ version: \"v4\" })".to_string(),
+        SchemaType::Ipv6 => "z.string().ip({ version: \"v6\" })".to_string(),
+        SchemaType::Ulid => "z.string().ulid()".to_string(),
+        SchemaType::Cuid => "z.string().cuid()".to_string(),
+        SchemaType::Time => "z.string().time()".to_string(),
+        SchemaType::Duration => "z.string().duration()".to_string(),
+        SchemaType::Base64 => "z.string().base64()".to_string(),
+        SchemaType::Jwt => "z.string().jwt()".to_string(),
+        // Zod has no built-in validators for these
+        SchemaType::HexColor => {
+            r"z.string().regex(/^#([0-9a-fA-F]{3,4}|[0-9a-fA-F]{6}|[0-9a-fA-F]{8})$/)".to_string()
+        }
+        SchemaType::Semver => {
+            r"z.string().regex(/^\d+\.\d+\.\d+(-[0-9A-Za-z.-]+)?(\+[0-9A-Za-z.-]+)?$/)".to_string()
+        }
+        SchemaType::Phone => r"z.string().regex(/^\+[1-9]\d{6,14}$/)".to_string(),
+        SchemaType::NumericString | SchemaType::WideNumericString if config.coerce_strings => {
+            "z.coerce.number().int()".to_string()
+        }
+        SchemaType::DecimalString if config.coerce_strings => "z.coerce.number()".to_string(),
+        // `z.coerce.boolean()` would turn "false" into `true`
+        SchemaType::BooleanString if config.coerce_strings => {
+            r#"z.enum(["true", "false"]).transform((v) => v === "true")"#.to_string()
+        }
+        SchemaType::NumericString | SchemaType::WideNumericString => {
+            r"z.string().regex(/^-?\d+$/)".to_string()
+        }
+        SchemaType::DecimalString => r"z.string().regex(/^-?\d+(\.\d+)?$/)".to_string(),
+        SchemaType::BooleanString => r#"z.enum(["true", "false"])"#.to_string(),
+        SchemaType::Custom(format) => {
+            format!("z.string().regex(/{}/)", escape_slashes(&format.pattern))
+        }
         SchemaType::Enum(values) => {
             if values.len() == 1 {
-                format!("z.literal(\"{}\")", values[0])
+                format!("z.literal({})", quote_string(&values[0]))
             } else {
-                let literals: Vec<String> = values
-                    .iter()
-                    .map(|v| format!("z.literal(\"{}\")", v))
-                    .collect();
-                format!("z.union([{}])", literals.join(", "))
+                let literals: Vec<String> = values.iter().map(|v| quote_string(v)).collect();
+                format!("z.enum([{}])", literals.join(", "))
             }
         }
         SchemaType::Array(inner) => {
-            let inner_type = generate_type(inner, indent, type_refs);
+            let inner_type = generate_type(inner, indent, type_refs, config);
             format!("z.array({})", inner_type)
         }
+        SchemaType::Tuple(types) => {
+            let type_strings: Vec<String> = types
+                .iter()
+                .map(|t| generate_type(t, indent, type_refs, config))
+                .collect();
+            format!("z.tuple([{}])", type_strings.join(", "))
+        }
         SchemaType::Optional(inner) => {
-            let inner_type = generate_type(inner, indent, type_refs);
+            let inner_type = generate_type(inner, indent, type_refs, config);
             format!("{}.optional()", inner_type)
         }
         SchemaType::Union(types) => {
+            let non_null: Vec<&SchemaType> = types
+                .iter()
+                .filter(|t| !matches!(t, SchemaType::Null))
+                .collect();
+            if non_null.len() < types.len() && !non_null.is_empty() {
+                let inner = if non_null.len() == 1 {
+                    non_null[0].clone()
+                } else {
+                    SchemaType::Union(non_null.into_iter().cloned().collect())
+                };
+                return format!("{}.nullable()", generate_type(&inner, indent, type_refs, config));
+            }
             if types.len() == 1 {
-                return generate_type(&types[0], indent, type_refs);
+                return generate_type(&types[0], indent, type_refs, config);
             }
             let type_strings: Vec<String> = types
                 .iter()
-                .map(|t| generate_type(t, indent, type_refs))
+                .map(|t| generate_type(t, indent, type_refs, config))
                 .collect();
             format!("z.union([{}])", type_strings.join(", "))
         }
+        SchemaType::DiscriminatedUnion { tag, variants } => {
+            let variant_strings: Vec<String> = variants
+                .iter()
+                .map(|v| generate_type(&SchemaType::Object(v.clone()), indent, type_refs, config))
+                .collect();
+            format!(
+                "z.discriminatedUnion({}, [{}])",
+                quote_string(tag),
+                variant_strings.join(", ")
+            )
+        }
+        SchemaType::Map(inner) => {
+            format!("z.record(z.string(), {})", generate_type(inner, indent, type_refs, config))
+        }
+        SchemaType::Ref(name) => format!("z.lazy(() => {}Schema)", name),
         SchemaType::Object(obj) => {
             let obj_key = format!("{:?}", obj);
             if let Some(type_name) = type_refs.get(&obj_key) {
                 format!("{}Schema", type_name)
             } else {
-                generate_inline_object(obj, indent, type_refs)
+                generate_inline_object(obj, indent, type_refs, config)
             }
         }
     }
 }
 
+/// Render a field's schema with its `.optional()` / `.nullable()` / `.nullish()` modifier,
+/// default and description
+pub fn generate_field_type(
+    field: &Field,
+    indent: &str,
+    type_refs: &HashMap<String, String>,
+    config: &GeneratorConfig,
+) -> String {
+    let field_type = format!(
+        "{}{}",
+        generate_type(&field.field_type, indent, type_refs, config),
+        constraint_refinements(field)
+    );
+    let mut field_type = match (field.optional, field.nullable) {
+        (true, true) => format!("{}.nullish()", field_type),
+        (true, false) => format!("{}.optional()", field_type),
+        (false, true) => format!("{}.nullable()", field_type),
+        (false, false) => field_type,
+    };
+    if let Some(value) = field.metadata.default_value.as_deref().and_then(default_literal) {
+        field_type.push_str(&format!(".default({})", value));
+    }
+    if let Some(description) = &field.metadata.description {
+        field_type.push_str(&format!(".describe({})", quote_string(description)));
+    }
+    field_type
+}
+
+/// Refinements for the bounds recorded on a field, e.g. `.min(1).max(40)`
+fn constraint_refinements(field: &Field) -> String {
+    let constraints = &field.metadata.constraints;
+    match field.field_type {
+        SchemaType::Integer | SchemaType::Int64 | SchemaType::Number | SchemaType::Decimal => {
+            let mut out = String::new();
+            if let Some(min) = constraints.minimum {
+                out.push_str(&format!(".min({})", min));
+            }
+            if let Some(max) = constraints.maximum {
+                out.push_str(&format!(".max({})", max));
+            }
+            out
+        }
+        SchemaType::String => length_refinements(constraints.min_length, constraints.max_length),
+        SchemaType::Array(_) => length_refinements(constraints.min_items, constraints.max_items),
+        _ => String::new(),
+    }
+}
+
+fn length_refinements(min: Option<usize>, max: Option<usize>) -> String {
+    if let (Some(min), Some(max)) = (min, max) {
+        if min == max && min > 0 {
+            return format!(".length({})", min);
+        }
+    }
+    let mut out = match min {
+        Some(1) => ".nonempty()".to_string(),
+        Some(n) if n > 1 => format!(".min({})", n),
+        _ => String::new(),
+    };
+    if let Some(max) = max {
+        out.push_str(&format!(".max({})", max));
+    }
+    out
+}
+
+/// Escape the `/`s of a pattern that aren't escaped already, so it can sit
+/// in a regex literal
+fn escape_slashes(pattern: &str) -> String {
+    let mut escaped = String::with_capacity(pattern.len());
+    let mut after_backslash = false;
+    for c in pattern.chars() {
+        if c == '/' && !after_backslash {
+            escaped.push('\\');
+        }
+        after_backslash = c == '\\' && !after_backslash;
+        escaped.push(c);
+    }
+    escaped
+}
+
 pub fn generate_inline_object(
     obj: &ObjectType,
     indent: &str,
     type_refs: &HashMap<String, String>,
+    config: &GeneratorConfig,
 ) -> String {
     if obj.fields.is_empty() {
         return "z.record(z.unknown())".to_string();
@@ -73,10 +225,7 @@ pub fn generate_inline_object(
 
     for field in &obj.fields {
         let field_name = format_field_name(&field.name);
-        let mut field_type = generate_type(&field.field_type, &inner_indent, type_refs);
-        if field.optional {
-            field_type = format!("{}.optional()", field_type);
-        }
+        let field_type = generate_field_type(field, &inner_indent, type_refs, config);
         lines.push(format!("{}{}: {},", inner_indent, field_name, field_type));
     }
 
@@ -1,14 +1,19 @@
 use anyhow::{Context, Result};
 use clap::{CommandFactory, Parser};
 use colored::Colorize;
-use std::io::{self, IsTerminal, Read, Write};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, IsTerminal, Read, Write};
 use std::path::{Path, PathBuf};
 use std::time::Instant;
 
-use mold_cli::cli::{Args, ColorMode, Command};
+use mold_cli::cli::{Args, ColorMode, Command, ConfigFile, InputFormat, ReportFormat};
 use mold_cli::generators::{Generator, GeneratorConfig, PrismaGenerator, TypeScriptGenerator, ZodGenerator};
-use mold_cli::parser::parse_json_string;
-use mold_cli::types::{MoldError, Schema, SchemaType};
+use mold_cli::parser::{
+    parse_lenient, parse_lenient_annotated, parse_yaml_documents, read_csv_samples,
+    read_json_stream, read_ndjson_samples, CsvOptions, InferenceConfig, MalformedRecord,
+    PathReport, Sampling, SchemaBuilder, SchemaReport, SchemaStats, StringPatterns,
+};
+use mold_cli::types::{Field, MoldError, Schema, SchemaType};
 use mold_cli::utils::{get_file_stem, suggest_similar_files, to_pascal_case, write_file};
 
 fn main() {
@@ -35,8 +40,12 @@ fn run() -> Result<()> {
     setup_color(&args);
     let is_tty = resolve_is_tty(&args);
 
-    // Resolve output formats
-    let (gen_ts, gen_zod, gen_prisma) = resolve_formats(&args)?;
+    // Resolve output formats (a report replaces generated code)
+    let (gen_ts, gen_zod, gen_prisma) = if args.report.is_some() {
+        (false, false, false)
+    } else {
+        resolve_formats(&args)?
+    };
 
     // Read input(s) - either files or stdin
     let inputs = read_inputs(&args)?;
@@ -47,21 +56,48 @@ fn run() -> Result<()> {
         ts_export_interfaces: args.ts_export,
         ts_readonly_fields: args.ts_readonly,
         zod_strict_objects: args.zod_strict,
+        coerce_strings: args.coerce,
         prisma_generate_relations: true,
     };
 
-    let multi_file = inputs.len() > 1;
+    let config_file = load_config_file(&args)?;
+    let mut heuristics = config_file.heuristics.clone();
+    heuristics.enabled &= !args.no_heuristics;
+
+    let inference = InferenceConfig {
+        flat_mode: args.flat,
+        detect_enums: args.enums,
+        enum_max_values: args.enum_max_values,
+        enum_min_occurrences: args.enum_min_occurrences,
+        map_min_keys: args.map_min_keys,
+        map_paths: args.map_paths.clone(),
+        detect_timestamps: args.detect_timestamps,
+        key_order: args.key_order,
+        discriminated_unions: !args.no_discriminated_unions,
+        infer_constraints: args.constraints,
+        heuristics,
+        patterns: load_patterns(&args, config_file)?,
+    };
+
+    // With --merge every input is a sample of one schema, otherwise each
+    // input gets a schema of its own
+    let batches: Vec<&[Input]> = if args.merge {
+        vec![&inputs[..]]
+    } else {
+        inputs.chunks(1).collect()
+    };
+    let multi_file = batches.len() > 1;
 
-    for (json_content, root_name, source_path) in &inputs {
+    for batch in batches {
         let start = Instant::now();
+        let source_path = &batch[0].path;
 
-        let schema = parse_json_string(json_content, &to_pascal_case(root_name), args.flat)
-            .with_context(|| {
-                match source_path {
-                    Some(p) => format!("Failed to parse '{}'", p.display()),
-                    None => "Failed to parse stdin".to_string(),
-                }
-            })?;
+        let (schema, stats) = load_schema(batch, &args, &inference)?;
+
+        if let Some(format) = args.report {
+            print_report(&SchemaReport::new(&schema, &stats), format, source_path.as_deref())?;
+            continue;
+        }
 
         // Verbose: print schema details
         if args.verbose {
@@ -149,13 +185,19 @@ fn run() -> Result<()> {
         // Summary (to stderr so it doesn't interfere with piped stdout)
         if !args.quiet {
             let (type_count, field_count) = count_stats(&schema);
+            let merged = if batch.len() > 1 {
+                format!(" from {} inputs", batch.len())
+            } else {
+                String::new()
+            };
             eprintln!(
-                "\n  {} Generated {} {} with {} {} in {:.0?}",
+                "\n  {} Generated {} {} with {} {}{} in {:.0?}",
                 "Done.".green().bold(),
                 type_count,
                 if type_count == 1 { "type" } else { "types" },
                 field_count,
                 if field_count == 1 { "field" } else { "fields" },
+                merged,
                 elapsed,
             );
         }
@@ -163,7 +205,7 @@ fn run() -> Result<()> {
 
     // Watch mode
     if args.watch {
-        watch_loop(&args, &config, gen_ts, gen_zod, gen_prisma)?;
+        watch_loop(&args, &config, &inference, gen_ts, gen_zod, gen_prisma)?;
     }
 
     Ok(())
@@ -216,12 +258,31 @@ fn resolve_formats(args: &Args) -> Result<(bool, bool, bool)> {
     Ok((ts, zod, prisma))
 }
 
+/// String formats from `--config` and `--disable-format`
+fn load_config_file(args: &Args) -> Result<ConfigFile> {
+    Ok(match &args.config {
+        Some(path) => ConfigFile::load(path)?,
+        None => ConfigFile::default(),
+    })
+}
+
+fn load_patterns(args: &Args, config: ConfigFile) -> Result<StringPatterns> {
+    let mut disabled = config.disable_formats;
+    disabled.extend(args.disable_formats.iter().cloned());
+    Ok(StringPatterns::new(config.formats, disabled)?)
+}
+
 // ---------------------------------------------------------------------------
 // Input reading (files + stdin)
 // ---------------------------------------------------------------------------
 
-/// Returns Vec of (json_content, root_name, optional_source_path)
-fn read_inputs(args: &Args) -> Result<Vec<(String, String, Option<PathBuf>)>> {
+/// A file to infer a schema from, or stdin when `path` is `None`
+struct Input {
+    name: String,
+    path: Option<PathBuf>,
+}
+
+fn read_inputs(args: &Args) -> Result<Vec<Input>> {
     // No files provided — try stdin
     if args.files.is_empty() {
         if io::stdin().is_terminal() {
@@ -231,20 +292,16 @@ fn read_inputs(args: &Args) -> Result<Vec<(String, String, Option<PathBuf>)>> {
                  Or pipe JSON: cat data.json | mold --ts"
             ));
         }
-        let mut input = String::new();
-        io::stdin().read_to_string(&mut input)?;
         let name = args.name.clone().unwrap_or_else(|| "Root".to_string());
-        return Ok(vec![(input, name, None)]);
+        return Ok(vec![Input { name, path: None }]);
     }
 
     let mut inputs = Vec::new();
     for path in &args.files {
         if path.as_os_str() == "-" {
             // Explicit stdin
-            let mut input = String::new();
-            io::stdin().read_to_string(&mut input)?;
             let name = args.name.clone().unwrap_or_else(|| "Root".to_string());
-            inputs.push((input, name, None));
+            inputs.push(Input { name, path: None });
         } else {
             if !path.exists() {
                 let hint = suggest_similar_files(path);
@@ -254,33 +311,161 @@ fn read_inputs(args: &Args) -> Result<Vec<(String, String, Option<PathBuf>)>> {
                     hint
                 ));
             }
-            let content = std::fs::read_to_string(path)
-                .with_context(|| format!("Failed to read '{}'", path.display()))?;
             let name = args
                 .name
                 .clone()
                 .unwrap_or_else(|| get_file_stem(path));
-            inputs.push((content, name, Some(path.clone())));
+            inputs.push(Input {
+                name,
+                path: Some(path.clone()),
+            });
         }
     }
 
     Ok(inputs)
 }
 
+fn resolve_input_format(args: &Args, path: Option<&Path>) -> InputFormat {
+    let format = args.input_format.unwrap_or_else(|| {
+        match path.and_then(|p| p.extension()).and_then(|ext| ext.to_str()) {
+            Some("ndjson") | Some("jsonl") => InputFormat::Ndjson,
+            Some("json5") | Some("jsonc") => InputFormat::Json5,
+            Some("yaml") | Some("yml") => InputFormat::Yaml,
+            Some("csv") | Some("tsv") => InputFormat::Csv,
+            _ => InputFormat::Json,
+        }
+    });
+    match format {
+        // Annotations live in comments, which plain JSON can't hold
+        InputFormat::Json if args.annotations => InputFormat::Json5,
+        format => format,
+    }
+}
+
+fn open_input(path: Option<&Path>) -> Result<Box<dyn BufRead>> {
+    match path {
+        Some(p) => {
+            let file = File::open(p).with_context(|| format!("Failed to read '{}'", p.display()))?;
+            Ok(Box::new(BufReader::new(file)))
+        }
+        None => Ok(Box::new(BufReader::new(io::stdin().lock()))),
+    }
+}
+
+/// Feed every sample in the input at `path` into `builder`
+fn load_samples(builder: &mut SchemaBuilder, path: Option<&Path>, args: &Args) -> Result<()> {
+    match resolve_input_format(args, path) {
+        InputFormat::Json => {
+            let summary = read_json_stream(open_input(path)?, builder, sampling(args))?;
+            if let Some(summary) = summary.filter(|s| s.sampled < s.elements) {
+                if !args.quiet {
+                    eprintln!(
+                        "  {} inferred from {} of {} array elements",
+                        "note:".cyan().bold(),
+                        summary.sampled,
+                        summary.elements
+                    );
+                }
+            }
+        }
+        InputFormat::Json5 => {
+            let mut content = String::new();
+            open_input(path)?.read_to_string(&mut content)?;
+            if args.annotations {
+                let (value, annotations) = parse_lenient_annotated(&content)?;
+                builder.add_annotations(annotations);
+                builder.add_sample(&value);
+            } else {
+                builder.add_sample(&parse_lenient(&content)?);
+            }
+        }
+        InputFormat::Yaml => {
+            let mut content = String::new();
+            open_input(path)?.read_to_string(&mut content)?;
+            for document in parse_yaml_documents(&content)? {
+                builder.add_sample(&document);
+            }
+        }
+        InputFormat::Csv => {
+            read_csv_samples(open_input(path)?, builder, &csv_options(args, path))?;
+        }
+        InputFormat::Ndjson => {
+            let malformed = read_ndjson_samples(open_input(path)?, builder)?;
+            if !args.quiet {
+                print_malformed_records(&malformed, path);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn csv_options(args: &Args, path: Option<&Path>) -> CsvOptions {
+    let is_tsv = path.and_then(|p| p.extension()).is_some_and(|ext| ext == "tsv");
+    CsvOptions {
+        delimiter: args.delimiter.unwrap_or(if is_tsv { b'\t' } else { b',' }),
+        has_headers: !args.no_header,
+    }
+}
+
+fn sampling(args: &Args) -> Sampling {
+    match (args.sample, args.reservoir) {
+        (Some(n), _) => Sampling::First(n),
+        (_, Some(n)) => Sampling::Reservoir(n),
+        (None, None) => Sampling::All,
+    }
+}
+
+/// Infer one schema from all of `inputs`, named after the first of them
+fn load_schema(
+    inputs: &[Input],
+    args: &Args,
+    inference: &InferenceConfig,
+) -> Result<(Schema, SchemaStats)> {
+    let mut builder = SchemaBuilder::new(to_pascal_case(&inputs[0].name), inference.clone());
+    for input in inputs {
+        load_samples(&mut builder, input.path.as_deref(), args)
+            .with_context(|| format!("Failed to parse {}", describe_input(input)))?;
+    }
+    builder.finish_with_stats().with_context(|| {
+        let sources: Vec<String> = inputs.iter().map(describe_input).collect();
+        format!("Failed to infer a schema from {}", sources.join(", "))
+    })
+}
+
+fn describe_input(input: &Input) -> String {
+    match &input.path {
+        Some(p) => format!("'{}'", p.display()),
+        None => "stdin".to_string(),
+    }
+}
+
+fn print_malformed_records(malformed: &[MalformedRecord], path: Option<&Path>) {
+    let source = path
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|| "stdin".to_string());
+    for record in malformed {
+        eprintln!(
+            "  {} skipped malformed record at {}:{}: {}",
+            "warning:".yellow().bold(),
+            source,
+            record.line,
+            record.message
+        );
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Stats
 // ---------------------------------------------------------------------------
 
 fn count_stats(schema: &Schema) -> (usize, usize) {
     let type_count = 1 + schema.nested_types.len();
-    let field_count = match &schema.root_type {
-        SchemaType::Object(obj) => obj.fields.len(),
-        _ => 0,
-    } + schema
-        .nested_types
-        .iter()
-        .map(|nt| nt.object.fields.len())
-        .sum::<usize>();
+    let field_count = schema.root_object().map_or(0, |obj| obj.fields.len())
+        + schema
+            .nested_types
+            .iter()
+            .map(|nt| nt.object.fields.len())
+            .sum::<usize>();
     (type_count, field_count)
 }
 
@@ -292,51 +477,212 @@ fn format_size(bytes: usize) -> String {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Report
+// ---------------------------------------------------------------------------
+
+fn print_report(report: &SchemaReport, format: ReportFormat, source: Option<&Path>) -> Result<()> {
+    if let ReportFormat::Json = format {
+        println!("{}", serde_json::to_string_pretty(report)?);
+        return Ok(());
+    }
+
+    let source = source
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|| "stdin".to_string());
+    println!(
+        "\n  {} {}",
+        source.bold(),
+        format!(
+            "({} {})",
+            report.samples,
+            if report.samples == 1 { "sample" } else { "samples" }
+        )
+        .dimmed()
+    );
+
+    let header = ["PATH", "PRESENT", "NULL", "TYPES", "DISTINCT", "RANGE", "EXAMPLES"];
+    let rows: Vec<[String; 7]> = report
+        .paths
+        .iter()
+        .map(|p| {
+            let types: Vec<String> = p
+                .types
+                .iter()
+                .map(|(kind, count)| format!("{} {}", kind, count))
+                .collect();
+            [
+                p.path.clone(),
+                p.presence.map(format_ratio).unwrap_or_else(|| "-".to_string()),
+                format_ratio(p.null_ratio),
+                types.join(", "),
+                match p.distinct {
+                    Some(0) => "-".to_string(),
+                    Some(n) => n.to_string(),
+                    None => "many".to_string(),
+                },
+                format_range(p),
+                truncate(&p.examples.join(", "), 40),
+            ]
+        })
+        .collect();
+
+    let mut widths = header.map(|h| h.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let line = |cells: [String; 7]| {
+        cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:<width$}", cell))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+    println!("\n  {}", line(header.map(String::from)).bold());
+    for (row, p) in rows.into_iter().zip(&report.paths) {
+        let text = line(row);
+        // Paths missing from some objects or sometimes null are the ones
+        // that need more samples or a closer look
+        if p.presence.is_some_and(|r| r < 1.0) || p.null_ratio > 0.0 {
+            println!("  {}", text.yellow());
+        } else {
+            println!("  {}", text);
+        }
+    }
+    Ok(())
+}
+
+fn format_ratio(ratio: f64) -> String {
+    format!("{:.0}%", ratio * 100.0)
+}
+
+fn format_range(p: &PathReport) -> String {
+    match (p.min, p.max, p.min_length, p.max_length) {
+        (Some(min), Some(max), _, _) => format!("{} – {}", min, max),
+        (_, _, Some(min), Some(max)) => format!("len {} – {}", min, max),
+        _ => String::new(),
+    }
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        s.to_string()
+    } else {
+        format!("{}…", s.chars().take(max - 1).collect::<String>())
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Verbose output
 // ---------------------------------------------------------------------------
 
 fn print_schema_details(schema: &Schema) {
     eprintln!("{}", "Schema details:".cyan().bold());
-    eprintln!("  Root: {}", schema.name.bold());
-    if let SchemaType::Object(obj) = &schema.root_type {
+    if schema.is_array_root() {
+        eprintln!(
+            "  Root: {} (array of {})",
+            schema.name.bold(),
+            schema.root_object_name().bold()
+        );
+    } else {
+        eprintln!("  Root: {}", schema.name.bold());
+    }
+    if let Some(obj) = schema.root_object() {
         for field in &obj.fields {
             eprintln!(
                 "    {} {}: {}{}",
                 "·".dimmed(),
                 field.name,
                 format_type_name(&field.field_type),
-                if field.optional { " (optional)".dimmed().to_string() } else { String::new() }
+                format_field_flags(field)
             );
         }
+    } else if let Some(union) = schema.root_union() {
+        eprintln!("    {} {}", "·".dimmed(), format_type_name(union));
     }
     for nt in &schema.nested_types {
-        eprintln!("  Nested: {}", nt.name.bold());
+        if nt.aliases.is_empty() {
+            eprintln!("  Nested: {}", nt.name.bold());
+        } else {
+            eprintln!(
+                "  Nested: {} {}",
+                nt.name.bold(),
+                format!("(merged from {})", nt.aliases.join(", ")).dimmed()
+            );
+        }
         for field in &nt.object.fields {
             eprintln!(
                 "    {} {}: {}{}",
                 "·".dimmed(),
                 field.name,
                 format_type_name(&field.field_type),
-                if field.optional { " (optional)".dimmed().to_string() } else { String::new() }
+                format_field_flags(field)
             );
         }
     }
     eprintln!();
 }
 
+fn format_field_flags(field: &Field) -> String {
+    let mut flags: Vec<String> = [
+        (field.optional, "optional"),
+        (field.nullable, "nullable"),
+        (field.metadata.is_unique, "unique"),
+        (field.metadata.is_readonly, "readonly"),
+    ]
+    .iter()
+    .filter(|(set, _)| *set)
+    .map(|(_, name)| name.to_string())
+    .collect();
+    if let Some(model) = &field.metadata.references {
+        flags.push(format!("→ {}", model));
+    }
+    if flags.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", flags.join(", ")).dimmed().to_string()
+    }
+}
+
 fn format_type_name(schema_type: &SchemaType) -> String {
     match schema_type {
         SchemaType::String => "String".to_string(),
         SchemaType::Number => "Number".to_string(),
         SchemaType::Integer => "Integer".to_string(),
+        SchemaType::Int64 => "Int64".to_string(),
+        SchemaType::BigInt => "BigInt".yellow().to_string(),
+        SchemaType::Decimal => "Decimal".to_string(),
         SchemaType::Boolean => "Boolean".to_string(),
         SchemaType::Null => "Null".to_string(),
         SchemaType::DateTime => "DateTime".yellow().to_string(),
+        SchemaType::EpochSeconds => "Epoch (s)".yellow().to_string(),
+        SchemaType::EpochMillis => "Epoch (ms)".yellow().to_string(),
         SchemaType::Date => "Date".yellow().to_string(),
         SchemaType::Uuid => "UUID".yellow().to_string(),
         SchemaType::Email => "Email".yellow().to_string(),
         SchemaType::Url => "URL".yellow().to_string(),
+        SchemaType::Custom(format) => format.name.yellow().to_string(),
+        SchemaType::NumericString => "NumericString".yellow().to_string(),
+        SchemaType::WideNumericString => "WideNumericString".yellow().to_string(),
+        SchemaType::DecimalString => "DecimalString".yellow().to_string(),
+        SchemaType::BooleanString => "BooleanString".yellow().to_string(),
+        SchemaType::Ipv4 => "IPv4".yellow().to_string(),
+        SchemaType::Ipv6 => "IPv6".yellow().to_string(),
+        SchemaType::Ulid => "ULID".yellow().to_string(),
+        SchemaType::Cuid => "CUID".yellow().to_string(),
+        SchemaType::Time => "Time".yellow().to_string(),
+        SchemaType::Duration => "Duration".yellow().to_string(),
+        SchemaType::HexColor => "HexColor".yellow().to_string(),
+        SchemaType::Semver => "Semver".yellow().to_string(),
+        SchemaType::Base64 => "Base64".yellow().to_string(),
+        SchemaType::Jwt => "JWT".yellow().to_string(),
+        SchemaType::Phone => "Phone".yellow().to_string(),
         SchemaType::Any => "Any".dimmed().to_string(),
         SchemaType::Array(inner) => format!("Array<{}>", format_type_name(inner)),
         SchemaType::Optional(inner) => format!("{}?", format_type_name(inner)),
@@ -345,6 +691,17 @@ fn format_type_name(schema_type: &SchemaType) -> String {
             parts.join(" | ")
         }
         SchemaType::Enum(values) => format!("Enum({})", values.join(", ")),
+        SchemaType::DiscriminatedUnion { tag, variants } => format!(
+            "Union on {} ({} variants)",
+            tag,
+            variants.len()
+        ),
+        SchemaType::Map(inner) => format!("Map<{}>", format_type_name(inner)),
+        SchemaType::Tuple(types) => {
+            let parts: Vec<String> = types.iter().map(format_type_name).collect();
+            format!("[{}]", parts.join(", "))
+        }
+        SchemaType::Ref(name) => format!("{} (recursive)", name).cyan().to_string(),
         SchemaType::Object(obj) => {
             if obj.fields.is_empty() {
                 "Object (empty)".to_string()
@@ -439,6 +796,7 @@ fn highlight_prisma_line(line: &str) -> String {
 fn watch_loop(
     args: &Args,
     config: &GeneratorConfig,
+    inference: &InferenceConfig,
     gen_ts: bool,
     gen_zod: bool,
     gen_prisma: bool,
@@ -473,7 +831,7 @@ fn watch_loop(
             eprintln!("\n  {} File changed, regenerating...", "⟳".cyan().bold());
 
             let start = Instant::now();
-            match regenerate(file_path, args, config, gen_ts, gen_zod, gen_prisma) {
+            match regenerate(file_path, args, config, inference, gen_ts, gen_zod, gen_prisma) {
                 Ok((schema, outputs)) => {
                     // Write or print outputs
                     if let Some(output_dir) = &args.output {
@@ -535,22 +893,32 @@ fn watch_loop(
     }
 }
 
+/// (format name, generated content, file extension)
+type GeneratedOutputs = Vec<(&'static str, String, &'static str)>;
+
 fn regenerate(
     file_path: &Path,
     args: &Args,
     config: &GeneratorConfig,
+    inference: &InferenceConfig,
     gen_ts: bool,
     gen_zod: bool,
     gen_prisma: bool,
-) -> Result<(Schema, Vec<(&'static str, String, &'static str)>)> {
-    let content = std::fs::read_to_string(file_path)?;
-    let root_name = args
-        .name
-        .clone()
-        .unwrap_or_else(|| get_file_stem(file_path));
-    let schema = parse_json_string(&content, &to_pascal_case(&root_name), args.flat)?;
-
-    let mut outputs: Vec<(&'static str, String, &'static str)> = Vec::new();
+) -> Result<(Schema, GeneratedOutputs)> {
+    let inputs = if args.merge {
+        read_inputs(args)?
+    } else {
+        vec![Input {
+            name: args
+                .name
+                .clone()
+                .unwrap_or_else(|| get_file_stem(file_path)),
+            path: Some(file_path.to_path_buf()),
+        }]
+    };
+    let (schema, _) = load_schema(&inputs, args, inference)?;
+
+    let mut outputs: GeneratedOutputs = Vec::new();
     if gen_ts {
         let gen = TypeScriptGenerator::new();
         outputs.push(("TypeScript", gen.generate(&schema, config)?, gen.file_extension()));
@@ -0,0 +1,373 @@
+use crate::types::{Field, ObjectType, SchemaType};
+use std::collections::HashMap;
+
+use super::stats::{path_key, ARRAY_ITEM_SEGMENT};
+
+/// Field metadata written as `@tags` in a comment next to the field, e.g.
+/// `"email": "a@b.co", // @unique @description Primary contact`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FieldAnnotation {
+    pub unique: bool,
+    pub readonly: bool,
+    pub optional: bool,
+    pub nullable: bool,
+    pub description: Option<String>,
+    pub default_value: Option<String>,
+    /// Replaces the inferred type (of the elements, for arrays)
+    pub field_type: Option<SchemaType>,
+}
+
+/// A malformed tag, `offset` bytes into the comment text
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagError {
+    pub offset: usize,
+    pub message: String,
+}
+
+impl FieldAnnotation {
+    /// Read the tags in a comment. `@` only starts a tag at the beginning of
+    /// a word, so addresses in descriptions are left alone. Comments without
+    /// tags give `None`.
+    pub fn parse(comment: &str) -> Result<Option<Self>, TagError> {
+        let starts: Vec<usize> = comment
+            .char_indices()
+            .filter(|&(i, c)| {
+                c == '@'
+                    && comment[..i]
+                        .chars()
+                        .next_back()
+                        .is_none_or(char::is_whitespace)
+            })
+            .map(|(i, _)| i)
+            .collect();
+        if starts.is_empty() {
+            return Ok(None);
+        }
+
+        let mut annotation = Self::default();
+        for (n, &start) in starts.iter().enumerate() {
+            let end = starts.get(n + 1).copied().unwrap_or(comment.len());
+            let tag = &comment[start + 1..end];
+            let name_len = tag
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                .unwrap_or(tag.len());
+            let (name, value) = (&tag[..name_len], tag[name_len..].trim());
+            let error = |message: String| TagError {
+                offset: start,
+                message,
+            };
+
+            let flag = match name {
+                "unique" => Some(&mut annotation.unique),
+                "readonly" => Some(&mut annotation.readonly),
+                "optional" => Some(&mut annotation.optional),
+                "nullable" => Some(&mut annotation.nullable),
+                _ => None,
+            };
+            if let Some(flag) = flag {
+                if !value.is_empty() {
+                    return Err(error(format!("@{} takes no value", name)));
+                }
+                *flag = true;
+                continue;
+            }
+
+            if !matches!(name, "description" | "default" | "type") {
+                return Err(error(format!("unknown annotation '@{}'", name)));
+            }
+            if value.is_empty() {
+                return Err(error(format!("@{} needs a value", name)));
+            }
+            match name {
+                "description" => annotation.description = Some(value.to_string()),
+                "default" => annotation.default_value = Some(value.to_string()),
+                _ => {
+                    let field_type = annotated_type(value)
+                        .ok_or_else(|| error(format!("unknown type '{}' in @type", value)))?;
+                    annotation.field_type = Some(field_type);
+                }
+            }
+        }
+        Ok(Some(annotation))
+    }
+
+    /// Combine with tags for the same field found elsewhere (e.g. in another
+    /// array element); values in `other` win
+    pub fn merge(&mut self, other: FieldAnnotation) {
+        self.unique |= other.unique;
+        self.readonly |= other.readonly;
+        self.optional |= other.optional;
+        self.nullable |= other.nullable;
+        self.description = other.description.or(self.description.take());
+        self.default_value = other.default_value.or(self.default_value.take());
+        self.field_type = other.field_type.or(self.field_type.take());
+    }
+
+    fn apply(&self, field: &mut Field) {
+        field.metadata.is_unique |= self.unique;
+        field.metadata.is_readonly |= self.readonly;
+        field.optional |= self.optional;
+        field.nullable |= self.nullable;
+        if let Some(description) = &self.description {
+            field.metadata.description = Some(description.clone());
+        }
+        if let Some(default_value) = &self.default_value {
+            field.metadata.default_value = Some(default_value.clone());
+        }
+        if let Some(field_type) = &self.field_type {
+            field.field_type = retype(&field.field_type, field_type);
+        }
+    }
+}
+
+/// Annotations found in a document, by field path (`users.email`)
+#[derive(Debug, Clone, Default)]
+pub struct Annotations {
+    fields: HashMap<String, FieldAnnotation>,
+}
+
+impl Annotations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, path: &[String], annotation: FieldAnnotation) {
+        self.fields
+            .entry(path_key(path))
+            .or_default()
+            .merge(annotation);
+    }
+
+    pub fn extend(&mut self, other: Annotations) {
+        for (key, annotation) in other.fields {
+            self.fields.entry(key).or_default().merge(annotation);
+        }
+    }
+
+    pub fn get(&self, path: &str) -> Option<&FieldAnnotation> {
+        self.fields.get(path)
+    }
+
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+}
+
+/// Type names accepted by `@type`, case-insensitively
+fn annotated_type(name: &str) -> Option<SchemaType> {
+    Some(match name.to_ascii_lowercase().as_str() {
+        "string" => SchemaType::String,
+        "number" | "float" => SchemaType::Number,
+        "integer" | "int" => SchemaType::Integer,
+        "bigint" => SchemaType::Int64,
+        "boolean" | "bool" => SchemaType::Boolean,
+        "decimal" => SchemaType::DecimalString,
+        "datetime" => SchemaType::DateTime,
+        "date" => SchemaType::Date,
+        "time" => SchemaType::Time,
+        "uuid" => SchemaType::Uuid,
+        "email" => SchemaType::Email,
+        "url" => SchemaType::Url,
+        "json" | "any" => SchemaType::Any,
+        _ => return None,
+    })
+}
+
+fn retype(current: &SchemaType, annotated: &SchemaType) -> SchemaType {
+    match current {
+        SchemaType::Array(inner) => SchemaType::Array(Box::new(retype(inner, annotated))),
+        SchemaType::Optional(inner) => SchemaType::Optional(Box::new(retype(inner, annotated))),
+        // `@type decimal` keeps numbers numbers and strings strings
+        _ if *annotated == SchemaType::DecimalString && is_number(current) => SchemaType::Decimal,
+        _ => annotated.clone(),
+    }
+}
+
+fn is_number(schema_type: &SchemaType) -> bool {
+    matches!(
+        schema_type,
+        SchemaType::Number | SchemaType::Integer | SchemaType::Int64 | SchemaType::BigInt
+    )
+}
+
+/// Apply annotations to the fields at their paths. Runs after inference and
+/// name heuristics, so annotations have the last word.
+pub fn apply_annotations(schema_type: SchemaType, annotations: &Annotations) -> SchemaType {
+    if annotations.is_empty() {
+        return schema_type;
+    }
+    annotate(schema_type, &mut Vec::new(), annotations)
+}
+
+fn annotate(
+    schema_type: SchemaType,
+    path: &mut Vec<String>,
+    annotations: &Annotations,
+) -> SchemaType {
+    match schema_type {
+        SchemaType::Object(obj) => SchemaType::Object(annotate_object(obj, path, annotations)),
+        SchemaType::Array(inner) => {
+            path.push(ARRAY_ITEM_SEGMENT.to_string());
+            let inner = annotate(*inner, path, annotations);
+            path.pop();
+            SchemaType::Array(Box::new(inner))
+        }
+        SchemaType::Tuple(types) => {
+            path.push(ARRAY_ITEM_SEGMENT.to_string());
+            let types = types
+                .into_iter()
+                .map(|t| annotate(t, path, annotations))
+                .collect();
+            path.pop();
+            SchemaType::Tuple(types)
+        }
+        SchemaType::Optional(inner) => {
+            SchemaType::Optional(Box::new(annotate(*inner, path, annotations)))
+        }
+        SchemaType::Union(types) => SchemaType::Union(
+            types
+                .into_iter()
+                .map(|t| annotate(t, path, annotations))
+                .collect(),
+        ),
+        SchemaType::DiscriminatedUnion { tag, variants } => SchemaType::DiscriminatedUnion {
+            tag,
+            variants: variants
+                .into_iter()
+                .map(|v| annotate_object(v, path, annotations))
+                .collect(),
+        },
+        other => other,
+    }
+}
+
+fn annotate_object(
+    obj: ObjectType,
+    path: &mut Vec<String>,
+    annotations: &Annotations,
+) -> ObjectType {
+    ObjectType::new(
+        obj.fields
+            .into_iter()
+            .map(|mut field| {
+                path.push(field.name.clone());
+                field.field_type = annotate(field.field_type, path, annotations);
+                if let Some(annotation) = annotations.get(&path_key(path)) {
+                    annotation.apply(&mut field);
+                }
+                path.pop();
+                field
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(segments: &[&str]) -> Vec<String> {
+        segments.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_tags() {
+        let annotation = FieldAnnotation::parse(" @unique @description Primary contact")
+            .unwrap()
+            .unwrap();
+        assert!(annotation.unique);
+        assert_eq!(annotation.description.as_deref(), Some("Primary contact"));
+
+        let annotation = FieldAnnotation::parse("@type Decimal @default 0")
+            .unwrap()
+            .unwrap();
+        assert_eq!(annotation.field_type, Some(SchemaType::DecimalString));
+        assert_eq!(annotation.default_value.as_deref(), Some("0"));
+    }
+
+    #[test]
+    fn test_parse_ignores_plain_comments_and_addresses() {
+        assert_eq!(
+            FieldAnnotation::parse(" ask ops@example.com").unwrap(),
+            None
+        );
+        let annotation = FieldAnnotation::parse("@description Mail admin@example.com")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            annotation.description.as_deref(),
+            Some("Mail admin@example.com")
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = FieldAnnotation::parse(" @unique @primary").unwrap_err();
+        assert_eq!(err.offset, 9);
+        assert!(err.message.contains("unknown annotation '@primary'"));
+        assert!(FieldAnnotation::parse("@type Money")
+            .unwrap_err()
+            .message
+            .contains("Money"));
+        assert!(FieldAnnotation::parse("@optional yes").is_err());
+        assert!(FieldAnnotation::parse("@description").is_err());
+    }
+
+    #[test]
+    fn test_apply_annotations() {
+        let mut annotations = Annotations::new();
+        annotations.insert(
+            &path(&["[]", "price"]),
+            FieldAnnotation::parse("@type decimal @optional")
+                .unwrap()
+                .unwrap(),
+        );
+        annotations.insert(
+            &path(&["[]", "tags"]),
+            FieldAnnotation::parse("@type uuid").unwrap().unwrap(),
+        );
+        let root = SchemaType::Array(Box::new(SchemaType::Object(ObjectType::new(vec![
+            Field::new("price", SchemaType::Number),
+            Field::new("tags", SchemaType::Array(Box::new(SchemaType::String))),
+            Field::new("name", SchemaType::String),
+        ]))));
+
+        let SchemaType::Array(inner) = apply_annotations(root, &annotations) else {
+            panic!("expected an array");
+        };
+        let SchemaType::Object(obj) = *inner else {
+            panic!("expected an object");
+        };
+        assert_eq!(obj.fields[0].field_type, SchemaType::Decimal);
+        assert!(obj.fields[0].optional);
+        assert_eq!(
+            obj.fields[1].field_type,
+            SchemaType::Array(Box::new(SchemaType::Uuid))
+        );
+        assert_eq!(obj.fields[2], Field::new("name", SchemaType::String));
+    }
+
+    #[test]
+    fn test_merge_keeps_flags_and_latest_values() {
+        let mut annotations = Annotations::new();
+        let key = path(&["email"]);
+        annotations.insert(
+            &key,
+            FieldAnnotation::parse("@unique @description Old")
+                .unwrap()
+                .unwrap(),
+        );
+        annotations.insert(
+            &key,
+            FieldAnnotation::parse("@description New").unwrap().unwrap(),
+        );
+        let annotation = annotations.get("email").unwrap();
+        assert!(annotation.unique);
+        assert_eq!(annotation.description.as_deref(), Some("New"));
+        assert_eq!(annotations.len(), 1);
+    }
+}
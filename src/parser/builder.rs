@@ -0,0 +1,390 @@
+use crate::types::{Field, MoldError, ObjectType, Schema, SchemaType};
+use anyhow::Result;
+use serde::de::{DeserializeSeed, Deserializer, SeqAccess};
+use serde_json::Value;
+
+use super::annotations::{apply_annotations, Annotations};
+use super::dedupe::dedupe_nested_types;
+use super::heuristics::apply_name_heuristics;
+use super::inference::{
+    extract_nested_types, infer_type, refine_types, unify_types, ArrayInference,
+};
+use super::ordering::{apply_key_order, order_type};
+use super::recursion::fold_recursive_type;
+use super::stats::{SchemaStats, ARRAY_ITEM_SEGMENT};
+use super::stream::InferSeed;
+use super::InferenceConfig;
+
+/// Folds any number of JSON samples of the same root type into one `Schema`.
+///
+/// Each sample is inferred and unified into the running root type as soon as
+/// it is added, so callers never need to keep the samples themselves around.
+pub struct SchemaBuilder {
+    name: String,
+    config: InferenceConfig,
+    root: Option<SchemaType>,
+    stats: SchemaStats,
+    samples: usize,
+    annotations: Annotations,
+    /// Root fields that may be missing from every sample
+    known_fields: Vec<Field>,
+}
+
+impl SchemaBuilder {
+    pub fn new(name: impl Into<String>, config: InferenceConfig) -> Self {
+        Self {
+            name: name.into(),
+            config,
+            root: None,
+            stats: SchemaStats::new(),
+            samples: 0,
+            annotations: Annotations::new(),
+            known_fields: Vec::new(),
+        }
+    }
+
+    pub fn add_sample(&mut self, value: &Value) {
+        let inferred = infer_type(
+            value,
+            &mut Vec::new(),
+            &mut self.stats,
+            &self.config.patterns,
+        );
+        self.add_inferred(inferred);
+    }
+
+    /// Field annotations read from the samples' comments, applied on top of
+    /// whatever is inferred
+    pub fn add_annotations(&mut self, annotations: Annotations) {
+        self.annotations.extend(annotations);
+    }
+
+    /// Root fields, in order, that exist even if no sample has a value for
+    /// them, such as CSV columns that are always empty. Those no sample had
+    /// are added to the root object as optional, right after the known field
+    /// before them; with no samples at all, the root is an object of just
+    /// these fields.
+    pub fn add_known_fields(&mut self, fields: impl IntoIterator<Item = Field>) {
+        self.known_fields.extend(fields);
+    }
+
+    /// Start a sample whose root is an array, to be fed one element at a
+    /// time. Element types are unified in batches as they arrive, so the
+    /// array itself never has to be in memory.
+    pub fn begin_array(&mut self) -> ArraySample<'_> {
+        ArraySample {
+            builder: self,
+            array: ArrayInference::default(),
+        }
+    }
+
+    /// Like [`add_sample`](Self::add_sample), inferring straight from the
+    /// deserializer's tokens so the sample's `Value` is never built
+    pub fn add_sample_from<'de, D: Deserializer<'de>>(
+        &mut self,
+        deserializer: D,
+    ) -> Result<(), D::Error> {
+        let inferred = InferSeed {
+            path: &mut Vec::new(),
+            stats: &mut self.stats,
+            patterns: &self.config.patterns,
+        }
+        .deserialize(deserializer)?;
+        self.add_inferred(inferred);
+        Ok(())
+    }
+
+    fn add_inferred(&mut self, inferred: SchemaType) {
+        self.root = Some(match self.root.take() {
+            Some(current) => unify_types(&[current, inferred]),
+            None => inferred,
+        });
+        self.samples += 1;
+    }
+
+    pub fn sample_count(&self) -> usize {
+        self.samples
+    }
+
+    pub fn finish(mut self) -> Result<Schema> {
+        self.build()
+    }
+
+    /// Like [`finish`](Self::finish), also handing back what was observed
+    /// at each path (for reports)
+    pub fn finish_with_stats(mut self) -> Result<(Schema, SchemaStats)> {
+        let schema = self.build()?;
+        Ok((schema, self.stats))
+    }
+
+    fn build(&mut self) -> Result<Schema> {
+        let inferred = match self.root.take() {
+            Some(root) => root,
+            None if !self.known_fields.is_empty() => {
+                SchemaType::Object(ObjectType::new(Vec::new()))
+            }
+            None => return Err(MoldError::NoSamples.into()),
+        };
+        let inferred = match inferred {
+            SchemaType::Object(mut obj) => {
+                let mut at = 0;
+                for field in &self.known_fields {
+                    match obj.fields.iter().position(|f| f.name == field.name) {
+                        Some(i) => at = i + 1,
+                        None => {
+                            obj.fields.insert(at, field.clone().optional());
+                            at += 1;
+                        }
+                    }
+                }
+                SchemaType::Object(obj)
+            }
+            other => other,
+        };
+        let root_type = refine_types(inferred, &mut Vec::new(), &self.config, &self.stats);
+        let root_type = apply_name_heuristics(root_type, &self.config.heuristics, &self.stats);
+        let root_type = apply_annotations(root_type, &self.annotations);
+        // Ordered before extraction, since nested types are found and named
+        // in field order
+        let root_type = order_type(root_type, self.config.key_order);
+
+        let mut schema = Schema::new(self.name.clone(), root_type);
+        let element = match (schema.root_object(), schema.root_union()) {
+            (Some(obj), _) => SchemaType::Object(obj.clone()),
+            (None, Some(union)) => union.clone(),
+            (None, None) => {
+                return Err(
+                    MoldError::InvalidRoot(describe_type(&schema.root_type).to_string()).into(),
+                )
+            }
+        };
+        if self.config.flat_mode {
+            return Ok(apply_key_order(schema, self.config.key_order));
+        }
+
+        // Array roots are named after their element type, so nested types of
+        // `Users = User[]` start with `User`
+        let mut path = vec![schema.root_object_name()];
+        let element = fold_recursive_type(&element, &mut path);
+        let mut nested_types = Vec::new();
+        extract_nested_types(&element, &mut path, &mut nested_types);
+        schema.root_type = if schema.is_array_root() {
+            SchemaType::Array(Box::new(element))
+        } else {
+            element
+        };
+
+        let schema = dedupe_nested_types(schema.with_nested_types(nested_types));
+        Ok(apply_key_order(schema, self.config.key_order))
+    }
+}
+
+/// A root-level array sample being filled in by [`SchemaBuilder::begin_array`].
+/// The result is the same as passing the whole array to `add_sample`.
+pub struct ArraySample<'a> {
+    builder: &'a mut SchemaBuilder,
+    array: ArrayInference,
+}
+
+impl ArraySample<'_> {
+    pub fn add_element(&mut self, value: &Value) {
+        let builder = &mut *self.builder;
+        let mut path = vec![ARRAY_ITEM_SEGMENT.to_string()];
+        let inferred = infer_type(value, &mut path, &mut builder.stats, &builder.config.patterns);
+        self.array.add(inferred);
+    }
+
+    /// Infer the next element of `seq` straight from its tokens, without
+    /// building its `Value`. Returns `false` at the end of the array.
+    pub fn add_element_from<'de, A: SeqAccess<'de>>(
+        &mut self,
+        seq: &mut A,
+    ) -> Result<bool, A::Error> {
+        let builder = &mut *self.builder;
+        let seed = InferSeed {
+            path: &mut vec![ARRAY_ITEM_SEGMENT.to_string()],
+            stats: &mut builder.stats,
+            patterns: &builder.config.patterns,
+        };
+        match seq.next_element_seed(seed)? {
+            Some(inferred) => {
+                self.array.add(inferred);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.array.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.array.is_empty()
+    }
+
+    /// Add the array to the builder as one sample
+    pub fn finish(self) {
+        let stats = &mut self.builder.stats;
+        stats.record(&[], &Value::Array(Vec::new()));
+        let inferred = self.array.finish(&[], stats);
+        self.builder.add_inferred(inferred);
+    }
+}
+
+fn describe_type(schema_type: &SchemaType) -> &'static str {
+    match schema_type {
+        SchemaType::Null => "null",
+        SchemaType::Boolean => "a boolean",
+        SchemaType::Number
+        | SchemaType::Integer
+        | SchemaType::Int64
+        | SchemaType::BigInt
+        | SchemaType::Decimal
+        | SchemaType::EpochSeconds
+        | SchemaType::EpochMillis => "a number",
+        SchemaType::Array(_) => "an array of non-objects",
+        SchemaType::Object(_) => "an object",
+        SchemaType::Map(_) => "a map",
+        SchemaType::Union(_) => "a mix of types",
+        _ => "a string",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_single_sample() {
+        let mut builder = SchemaBuilder::new("User", InferenceConfig::default());
+        builder.add_sample(&json!({"id": 1, "name": "Ada"}));
+        let schema = builder.finish().unwrap();
+
+        assert_eq!(schema.name, "User");
+        if let SchemaType::Object(obj) = &schema.root_type {
+            assert_eq!(obj.fields.len(), 2);
+            assert!(obj.fields.iter().all(|f| !f.optional));
+        } else {
+            panic!("Expected Object type");
+        }
+    }
+
+    #[test]
+    fn test_samples_are_merged() {
+        let mut builder = SchemaBuilder::new("Event", InferenceConfig::default());
+        builder.add_sample(&json!({"id": 1, "user": {"name": "Ada"}}));
+        builder.add_sample(&json!({"id": 2, "user": {"name": "Bob", "age": 3}, "tag": null}));
+        builder.add_sample(&json!({"id": 3, "tag": "x"}));
+        assert_eq!(builder.sample_count(), 3);
+
+        let schema = builder.finish().unwrap();
+        let obj = match &schema.root_type {
+            SchemaType::Object(obj) => obj,
+            _ => panic!("Expected Object type"),
+        };
+        let user = obj.fields.iter().find(|f| f.name == "user").unwrap();
+        assert!(user.optional);
+        let tag = obj.fields.iter().find(|f| f.name == "tag").unwrap();
+        assert!(tag.optional && tag.nullable);
+        assert_eq!(tag.field_type, SchemaType::String);
+
+        assert_eq!(schema.nested_types.len(), 1);
+        assert_eq!(schema.nested_types[0].name, "EventUser");
+        let user_fields = &schema.nested_types[0].object.fields;
+        assert!(user_fields.iter().find(|f| f.name == "age").unwrap().optional);
+    }
+
+    #[test]
+    fn test_enums_use_stats_from_all_samples() {
+        let config = InferenceConfig {
+            detect_enums: true,
+            ..Default::default()
+        };
+        let mut builder = SchemaBuilder::new("Event", config);
+        for kind in ["click", "view", "click", "click"] {
+            builder.add_sample(&json!({ "kind": kind }));
+        }
+        let schema = builder.finish().unwrap();
+        if let SchemaType::Object(obj) = &schema.root_type {
+            assert_eq!(
+                obj.fields[0].field_type,
+                SchemaType::Enum(vec!["click".to_string(), "view".to_string()])
+            );
+        }
+    }
+
+    #[test]
+    fn test_no_samples_is_an_error() {
+        let builder = SchemaBuilder::new("Empty", InferenceConfig::default());
+        assert!(builder.finish().is_err());
+    }
+
+    #[test]
+    fn test_non_object_root_is_an_error() {
+        let mut builder = SchemaBuilder::new("Bad", InferenceConfig::default());
+        builder.add_sample(&json!("just a string"));
+        let err = builder.finish().unwrap_err();
+        assert!(err.to_string().contains("a string"));
+    }
+
+    #[test]
+    fn test_id_keyed_root_stays_an_object() {
+        let mut builder = SchemaBuilder::new("Rows", InferenceConfig::default());
+        builder.add_sample(&json!({"1": {"a": 1}, "2": {"a": 2}, "3": {"a": 3}}));
+        let schema = builder.finish().unwrap();
+        let names: Vec<&str> = schema
+            .root_object()
+            .unwrap()
+            .fields
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["1", "2", "3"]);
+
+        let mut builder = SchemaBuilder::new("Daily", InferenceConfig::default());
+        builder.add_sample(&json!({"2024-01-01": 1, "2024-01-02": 2, "2024-01-03": 3}));
+        assert!(builder.finish().unwrap().root_object().is_some());
+    }
+
+    #[test]
+    fn test_describe_map() {
+        let map = SchemaType::Map(Box::new(SchemaType::Integer));
+        assert_eq!(describe_type(&map), "a map");
+    }
+
+    #[test]
+    fn test_array_root_of_objects() {
+        let mut builder = SchemaBuilder::new("Users", InferenceConfig::default());
+        builder.add_sample(&json!([
+            {"id": 1, "address": {"city": "Oslo"}},
+            {"id": 2}
+        ]));
+        let schema = builder.finish().unwrap();
+
+        assert!(schema.is_array_root());
+        assert_eq!(schema.root_object_name(), "User");
+        let names: Vec<&str> = schema
+            .nested_types
+            .iter()
+            .map(|n| n.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["UserAddress"]);
+        let address = schema
+            .root_object()
+            .unwrap()
+            .fields
+            .iter()
+            .find(|f| f.name == "address")
+            .unwrap();
+        assert!(address.optional);
+    }
+
+    #[test]
+    fn test_array_root_of_scalars_is_an_error() {
+        let mut builder = SchemaBuilder::new("Ids", InferenceConfig::default());
+        builder.add_sample(&json!([1, 2, 3]));
+        assert!(builder.finish().is_err());
+    }
+}
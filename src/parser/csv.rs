@@ -0,0 +1,285 @@
+use crate::types::{Field, MoldError, Schema, SchemaType};
+use anyhow::Result;
+use serde_json::{Map, Number, Value};
+use std::io::Read;
+
+use super::builder::SchemaBuilder;
+use super::InferenceConfig;
+
+/// How to read CSV input
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    pub delimiter: u8,
+    /// Whether the first row names the columns; otherwise they're named
+    /// `column1`, `column2`, ...
+    pub has_headers: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            has_headers: true,
+        }
+    }
+}
+
+/// What every non-empty cell of a column parses as
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ColumnKind {
+    Boolean,
+    Integer,
+    Number,
+    Text,
+}
+
+impl ColumnKind {
+    fn of(cell: &str) -> Self {
+        if cell.eq_ignore_ascii_case("true") || cell.eq_ignore_ascii_case("false") {
+            return ColumnKind::Boolean;
+        }
+        // Leading zeros (zip codes, account numbers) are kept as text
+        let digits = cell.strip_prefix('-').unwrap_or(cell);
+        if digits.starts_with('0') && digits.len() > 1 && !digits.starts_with("0.") {
+            return ColumnKind::Text;
+        }
+        match cell.parse::<Number>() {
+            Ok(_) if !cell.contains(['.', 'e', 'E']) => ColumnKind::Integer,
+            Ok(_) => ColumnKind::Number,
+            Err(_) => ColumnKind::Text,
+        }
+    }
+
+    fn join(self, other: Self) -> Self {
+        use ColumnKind::*;
+        match (self, other) {
+            (a, b) if a == b => a,
+            (Integer, Number) | (Number, Integer) => Number,
+            _ => Text,
+        }
+    }
+
+    fn value(self, cell: &str) -> Value {
+        match self {
+            ColumnKind::Boolean => Value::Bool(cell.eq_ignore_ascii_case("true")),
+            ColumnKind::Integer | ColumnKind::Number => cell
+                .parse::<Number>()
+                .map_or_else(|_| Value::String(cell.to_string()), Value::Number),
+            ColumnKind::Text => Value::String(cell.to_string()),
+        }
+    }
+}
+
+/// Feed every row of a CSV file into `builder` as one flat object sample.
+/// A column's type is decided from all of its rows: booleans, integers and
+/// numbers when every non-empty cell is one, text otherwise (where string
+/// formats are detected as usual). Empty cells leave the key out, so
+/// columns with gaps become optional, and columns that are always empty (or
+/// only have a header) are optional strings. Since no type is known before
+/// the last row, the rows are held in memory until then. Returns the number
+/// of rows read.
+pub fn read_csv_samples<R: Read>(
+    reader: R,
+    builder: &mut SchemaBuilder,
+    options: &CsvOptions,
+) -> Result<usize, MoldError> {
+    let mut reader = ::csv::ReaderBuilder::new()
+        .delimiter(options.delimiter)
+        .has_headers(options.has_headers)
+        .flexible(true)
+        .trim(::csv::Trim::All)
+        .from_reader(reader);
+
+    let mut columns: Vec<String> = Vec::new();
+    if options.has_headers {
+        for (i, header) in reader.headers()?.iter().enumerate() {
+            columns.push(column_name(header, i, &columns));
+        }
+    }
+
+    let mut rows = Vec::new();
+    let mut kinds: Vec<Option<ColumnKind>> = vec![None; columns.len()];
+    for record in reader.records() {
+        let record = record?;
+        for (i, cell) in record.iter().enumerate() {
+            if i == columns.len() {
+                // Rows wider than the header get placeholder names
+                columns.push(column_name("", i, &columns));
+                kinds.push(None);
+            }
+            if !cell.is_empty() {
+                let kind = ColumnKind::of(cell);
+                kinds[i] = Some(kinds[i].map_or(kind, |k| k.join(kind)));
+            }
+        }
+        rows.push(record);
+    }
+
+    for record in &rows {
+        let mut row = Map::new();
+        for (i, cell) in record.iter().enumerate() {
+            if let (false, Some(kind)) = (cell.is_empty(), kinds[i]) {
+                row.insert(columns[i].clone(), kind.value(cell));
+            }
+        }
+        builder.add_sample(&Value::Object(row));
+    }
+    // Every column, so the always-empty ones keep their place in the header
+    builder.add_known_fields(
+        columns
+            .iter()
+            .map(|name| Field::new(name.clone(), SchemaType::String)),
+    );
+    Ok(rows.len())
+}
+
+pub fn parse_csv_string(
+    input: &str,
+    name: &str,
+    config: &InferenceConfig,
+    options: &CsvOptions,
+) -> Result<Schema> {
+    let mut builder = SchemaBuilder::new(name, config.clone());
+    read_csv_samples(input.as_bytes(), &mut builder, options)?;
+    builder.finish()
+}
+
+/// A header as a field name: blank headers become `column<n>` and repeats
+/// get a numeric suffix
+fn column_name(header: &str, index: usize, taken: &[String]) -> String {
+    let base = match header {
+        "" => format!("column{}", index + 1),
+        header => header.to_string(),
+    };
+    let mut name = base.clone();
+    let mut n = 2;
+    while taken.contains(&name) {
+        name = format!("{}_{}", base, n);
+        n += 1;
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{KeyOrder, SchemaType};
+
+    fn field_types(input: &str, options: &CsvOptions) -> Vec<(String, SchemaType, bool)> {
+        let schema = parse_csv_string(input, "Row", &InferenceConfig::default(), options).unwrap();
+        let obj = schema.root_object().unwrap().clone();
+        obj.fields
+            .into_iter()
+            .map(|f| (f.name, f.field_type, f.optional))
+            .collect()
+    }
+
+    #[test]
+    fn test_column_types_from_all_rows() {
+        let input = "\
+id,price,active,email,zip,code,note
+1,9.99,true,a@b.co,02134,7,
+2,10,FALSE,c@d.io,10001,x9,late
+";
+        let fields = field_types(input, &CsvOptions::default());
+        assert_eq!(
+            fields,
+            vec![
+                ("active".to_string(), SchemaType::Boolean, false),
+                ("code".to_string(), SchemaType::String, false),
+                ("email".to_string(), SchemaType::Email, false),
+                ("id".to_string(), SchemaType::Integer, false),
+                ("note".to_string(), SchemaType::String, true),
+                ("price".to_string(), SchemaType::Number, false),
+                ("zip".to_string(), SchemaType::String, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_delimiter_and_missing_headers() {
+        let options = CsvOptions {
+            delimiter: b'\t',
+            has_headers: false,
+        };
+        let fields = field_types("1\tAda\n2\tGrace\t3\n", &options);
+        let names: Vec<&str> = fields.iter().map(|(n, _, _)| n.as_str()).collect();
+        assert_eq!(names, vec!["column1", "column2", "column3"]);
+        assert_eq!(fields[0].1, SchemaType::Integer);
+        assert!(fields[2].2);
+    }
+
+    #[test]
+    fn test_header_names_are_made_unique() {
+        let fields = field_types("name,,name\na,b,c\n", &CsvOptions::default());
+        let names: Vec<&str> = fields.iter().map(|(n, _, _)| n.as_str()).collect();
+        assert_eq!(names, vec!["column2", "name", "name_2"]);
+    }
+
+    #[test]
+    fn test_empty_columns_are_optional_strings() {
+        let fields = field_types("id,note,\n1,,\n2,,\n", &CsvOptions::default());
+        assert_eq!(
+            fields,
+            vec![
+                ("column3".to_string(), SchemaType::String, true),
+                ("id".to_string(), SchemaType::Integer, false),
+                ("note".to_string(), SchemaType::String, true),
+            ]
+        );
+
+        let fields = field_types("id,name\n", &CsvOptions::default());
+        assert_eq!(
+            fields,
+            vec![
+                ("id".to_string(), SchemaType::String, true),
+                ("name".to_string(), SchemaType::String, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_empty_columns_keep_header_position() {
+        let config = InferenceConfig {
+            key_order: KeyOrder::Source,
+            ..InferenceConfig::default()
+        };
+        let input = "id,note,name,\n1,,Ada,\n2,,Grace,\n";
+        let schema = parse_csv_string(input, "Row", &config, &CsvOptions::default()).unwrap();
+        let names: Vec<&str> = schema
+            .root_object()
+            .unwrap()
+            .fields
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["id", "note", "name", "column4"]);
+    }
+
+    #[test]
+    fn test_quoted_cells() {
+        let input = "title,count\n\"Hello, world\",\"3\"\n\"Say \"\"hi\"\"\",4\n";
+        let fields = field_types(input, &CsvOptions::default());
+        assert_eq!(fields[0].1, SchemaType::Integer);
+        assert_eq!(fields[1].1, SchemaType::String);
+    }
+
+    #[test]
+    fn test_column_kind() {
+        assert_eq!(ColumnKind::of("0"), ColumnKind::Integer);
+        assert_eq!(ColumnKind::of("-12"), ColumnKind::Integer);
+        assert_eq!(ColumnKind::of("0.5"), ColumnKind::Number);
+        assert_eq!(ColumnKind::of("1e3"), ColumnKind::Number);
+        assert_eq!(ColumnKind::of("007"), ColumnKind::Text);
+        assert_eq!(ColumnKind::of("True"), ColumnKind::Boolean);
+        assert_eq!(ColumnKind::of("NaN"), ColumnKind::Text);
+        assert_eq!(
+            ColumnKind::Integer.join(ColumnKind::Number),
+            ColumnKind::Number
+        );
+        assert_eq!(
+            ColumnKind::Boolean.join(ColumnKind::Integer),
+            ColumnKind::Text
+        );
+    }
+}
@@ -0,0 +1,535 @@
+use crate::types::{Field, NestedType, ObjectType, Schema, SchemaType};
+use crate::utils::split_pascal_words;
+use std::collections::{HashMap, HashSet};
+
+use super::inference::merge_objects;
+
+/// Collapse nested types that describe the same shape into one shared type.
+///
+/// Two types are merged when their fields are equal, or when one is a
+/// subset of the other covering at least half of its fields (the extra
+/// fields become optional). Merging a group can make parents equal, so
+/// the pass repeats until nothing changes.
+pub fn dedupe_nested_types(mut schema: Schema) -> Schema {
+    loop {
+        let groups = find_groups(&schema.nested_types);
+        if groups.is_empty() {
+            return schema;
+        }
+
+        let mut replacements = HashMap::new();
+        for group in &groups {
+            let members: Vec<&ObjectType> = group
+                .iter()
+                .map(|&i| &schema.nested_types[i].object)
+                .collect();
+            let merged = merge_objects(&members);
+            for member in members {
+                replacements.insert(type_key(member), merged.clone());
+            }
+        }
+
+        let root_type = rewrite_root(&schema.root_type, &replacements);
+        let rewritten: Vec<NestedType> = schema
+            .nested_types
+            .iter()
+            .map(|nt| NestedType {
+                object: rewrite_object(&nt.object, &replacements),
+                ..nt.clone()
+            })
+            .collect();
+
+        schema.root_type = root_type;
+        let root_name = schema.root_object_name();
+        let collapsed = collapse_equal(rewritten, &root_name);
+        let collapsed = rename_refs_in(&mut schema.root_type, collapsed);
+        schema.nested_types = order_by_use(&schema.root_type, collapsed);
+    }
+}
+
+/// Greedily group compatible types; a type joins the first group whose
+/// members are all compatible with it. Only groups of two or more are kept.
+fn find_groups(nested_types: &[NestedType]) -> Vec<Vec<usize>> {
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for (i, nt) in nested_types.iter().enumerate() {
+        let target = groups.iter_mut().find(|group| {
+            group
+                .iter()
+                .all(|&j| is_compatible(&nested_types[j].object, &nt.object))
+        });
+        match target {
+            Some(group) => group.push(i),
+            None => groups.push(vec![i]),
+        }
+    }
+    groups.retain(|group| group.len() > 1);
+    groups
+}
+
+fn is_compatible(a: &ObjectType, b: &ObjectType) -> bool {
+    is_compatible_by(a, b, |x, y| x == y)
+}
+
+/// Whether two objects share a shape: one's fields are a subset of the
+/// other's (at least two of them, covering at least half), and shared
+/// fields have matching types according to `same_type`.
+pub(super) fn is_compatible_by(
+    a: &ObjectType,
+    b: &ObjectType,
+    same_type: impl Fn(&SchemaType, &SchemaType) -> bool,
+) -> bool {
+    let (small, large) = if a.fields.len() <= b.fields.len() {
+        (a, b)
+    } else {
+        (b, a)
+    };
+    if small.fields.is_empty() {
+        return false;
+    }
+    if small.fields.len() < large.fields.len()
+        && (small.fields.len() < 2 || small.fields.len() * 2 < large.fields.len())
+    {
+        return false;
+    }
+    small.fields.iter().all(|field| {
+        large.fields.iter().any(|other| {
+            other.name == field.name && same_type(&other.field_type, &field.field_type)
+        })
+    })
+}
+
+fn type_key(obj: &ObjectType) -> String {
+    format!("{:?}", obj)
+}
+
+fn rewrite_root(root_type: &SchemaType, replacements: &HashMap<String, ObjectType>) -> SchemaType {
+    match root_type {
+        SchemaType::Object(obj) => SchemaType::Object(rewrite_fields(obj, replacements)),
+        SchemaType::Array(inner) => match inner.as_ref() {
+            SchemaType::Object(obj) => SchemaType::Array(Box::new(SchemaType::Object(
+                rewrite_fields(obj, replacements),
+            ))),
+            other => SchemaType::Array(Box::new(rewrite_type(other, replacements))),
+        },
+        other => rewrite_type(other, replacements),
+    }
+}
+
+fn rewrite_type(
+    schema_type: &SchemaType,
+    replacements: &HashMap<String, ObjectType>,
+) -> SchemaType {
+    match schema_type {
+        SchemaType::Object(obj) => SchemaType::Object(rewrite_object(obj, replacements)),
+        SchemaType::Array(inner) => SchemaType::Array(Box::new(rewrite_type(inner, replacements))),
+        SchemaType::Optional(inner) => {
+            SchemaType::Optional(Box::new(rewrite_type(inner, replacements)))
+        }
+        SchemaType::Map(inner) => SchemaType::Map(Box::new(rewrite_type(inner, replacements))),
+        SchemaType::Union(types) => SchemaType::Union(
+            types
+                .iter()
+                .map(|t| rewrite_type(t, replacements))
+                .collect(),
+        ),
+        SchemaType::Tuple(types) => SchemaType::Tuple(
+            types
+                .iter()
+                .map(|t| rewrite_type(t, replacements))
+                .collect(),
+        ),
+        SchemaType::DiscriminatedUnion { tag, variants } => SchemaType::DiscriminatedUnion {
+            tag: tag.clone(),
+            variants: variants
+                .iter()
+                .map(|v| rewrite_object(v, replacements))
+                .collect(),
+        },
+        other => other.clone(),
+    }
+}
+
+/// Swap in the merged object if this one was absorbed, then rewrite its
+/// children. Being a pure function of the input keeps every occurrence of a
+/// type identical, which the `{:?}`-keyed generator refs rely on.
+fn rewrite_object(obj: &ObjectType, replacements: &HashMap<String, ObjectType>) -> ObjectType {
+    let obj = replacements.get(&type_key(obj)).unwrap_or(obj);
+    rewrite_fields(obj, replacements)
+}
+
+fn rewrite_fields(obj: &ObjectType, replacements: &HashMap<String, ObjectType>) -> ObjectType {
+    ObjectType::new(
+        obj.fields
+            .iter()
+            .map(|field| Field {
+                field_type: rewrite_type(&field.field_type, replacements),
+                ..field.clone()
+            })
+            .collect(),
+    )
+}
+
+/// Point recursive `Ref`s at the surviving name of a merged type. Every
+/// occurrence is renamed, so the `{:?}` keys stay consistent.
+fn rename_refs_in(root_type: &mut SchemaType, nested_types: Vec<NestedType>) -> Vec<NestedType> {
+    let renames: HashMap<&str, &str> = nested_types
+        .iter()
+        .flat_map(|nt| nt.aliases.iter().map(|a| (a.as_str(), nt.name.as_str())))
+        .collect();
+    if renames.is_empty() {
+        return nested_types;
+    }
+
+    *root_type = rename_refs(root_type, &renames);
+    nested_types
+        .iter()
+        .map(|nt| NestedType {
+            object: rename_object_refs(&nt.object, &renames),
+            ..nt.clone()
+        })
+        .collect()
+}
+
+fn rename_refs(schema_type: &SchemaType, renames: &HashMap<&str, &str>) -> SchemaType {
+    match schema_type {
+        SchemaType::Ref(name) => match renames.get(name.as_str()) {
+            Some(new_name) => SchemaType::Ref(new_name.to_string()),
+            None => SchemaType::Ref(name.clone()),
+        },
+        SchemaType::Object(obj) => SchemaType::Object(rename_object_refs(obj, renames)),
+        SchemaType::Array(inner) => SchemaType::Array(Box::new(rename_refs(inner, renames))),
+        SchemaType::Optional(inner) => SchemaType::Optional(Box::new(rename_refs(inner, renames))),
+        SchemaType::Map(inner) => SchemaType::Map(Box::new(rename_refs(inner, renames))),
+        SchemaType::Union(types) => {
+            SchemaType::Union(types.iter().map(|t| rename_refs(t, renames)).collect())
+        }
+        SchemaType::Tuple(types) => {
+            SchemaType::Tuple(types.iter().map(|t| rename_refs(t, renames)).collect())
+        }
+        SchemaType::DiscriminatedUnion { tag, variants } => SchemaType::DiscriminatedUnion {
+            tag: tag.clone(),
+            variants: variants
+                .iter()
+                .map(|v| rename_object_refs(v, renames))
+                .collect(),
+        },
+        other => other.clone(),
+    }
+}
+
+fn rename_object_refs(obj: &ObjectType, renames: &HashMap<&str, &str>) -> ObjectType {
+    ObjectType::new(
+        obj.fields
+            .iter()
+            .map(|field| Field {
+                field_type: rename_refs(&field.field_type, renames),
+                ..field.clone()
+            })
+            .collect(),
+    )
+}
+
+/// Keep one entry per distinct object, naming merged entries after the
+/// words their names have in common.
+fn collapse_equal(nested_types: Vec<NestedType>, root_name: &str) -> Vec<NestedType> {
+    let mut buckets: Vec<(String, Vec<NestedType>)> = Vec::new();
+    for nt in nested_types {
+        let key = type_key(&nt.object);
+        match buckets.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, bucket)) => bucket.push(nt),
+            None => buckets.push((key, vec![nt])),
+        }
+    }
+
+    let all_names: Vec<String> = buckets
+        .iter()
+        .flat_map(|(_, bucket)| bucket.iter().map(|nt| nt.name.clone()))
+        .collect();
+    let mut taken: HashSet<String> = HashSet::new();
+    taken.insert(root_name.to_string());
+
+    let mut result = Vec::new();
+    for (_, mut bucket) in buckets {
+        if bucket.len() == 1 {
+            let nt = bucket.remove(0);
+            taken.insert(nt.name.clone());
+            result.push(nt);
+            continue;
+        }
+
+        let names: Vec<String> = bucket.iter().map(|nt| nt.name.clone()).collect();
+        let name = common_type_name(&names)
+            .filter(|candidate| {
+                !taken.contains(candidate)
+                    && (names.contains(candidate) || !all_names.contains(candidate))
+            })
+            .unwrap_or_else(|| names[0].clone());
+
+        let mut aliases = Vec::new();
+        for nt in &bucket {
+            for alias in nt.aliases.iter().chain(std::iter::once(&nt.name)) {
+                if *alias != name && !aliases.contains(alias) {
+                    aliases.push(alias.clone());
+                }
+            }
+        }
+
+        taken.insert(name.clone());
+        let object = bucket.remove(0).object;
+        result.push(NestedType::new(name, object).with_aliases(aliases));
+    }
+    result
+}
+
+/// The longest run of trailing PascalCase words shared by every name
+/// (e.g., "UserBillingAddress" + "UserShippingAddress" → "Address"). A bare
+/// "Item" is too generic to be useful, so it doesn't count.
+fn common_type_name(names: &[String]) -> Option<String> {
+    let words: Vec<Vec<String>> = names.iter().map(|n| split_pascal_words(n)).collect();
+    let shortest = words.iter().map(|w| w.len()).min()?;
+
+    let mut suffix_len = 0;
+    while suffix_len < shortest {
+        let word = &words[0][words[0].len() - 1 - suffix_len];
+        if words.iter().all(|w| &w[w.len() - 1 - suffix_len] == word) {
+            suffix_len += 1;
+        } else {
+            break;
+        }
+    }
+
+    let suffix = &words[0][words[0].len() - suffix_len..];
+    match suffix {
+        [] => None,
+        [only] if only == "Item" => None,
+        _ => Some(suffix.concat()),
+    }
+}
+
+/// Restore extraction order (children before their parents) by walking the
+/// rewritten tree, so merged types land before every type that uses them.
+fn order_by_use(root_type: &SchemaType, nested_types: Vec<NestedType>) -> Vec<NestedType> {
+    let mut by_key: HashMap<String, NestedType> = HashMap::new();
+    let mut keys = Vec::new();
+    for nt in nested_types {
+        let key = type_key(&nt.object);
+        keys.push(key.clone());
+        by_key.insert(key, nt);
+    }
+
+    let mut ordered = Vec::new();
+    match root_type {
+        SchemaType::Object(obj) => visit_fields(obj, &mut by_key, &mut ordered),
+        SchemaType::Array(inner) => match inner.as_ref() {
+            SchemaType::Object(obj) => visit_fields(obj, &mut by_key, &mut ordered),
+            other => visit_type(other, &mut by_key, &mut ordered),
+        },
+        other => visit_type(other, &mut by_key, &mut ordered),
+    }
+
+    // Anything the walk didn't reach keeps its previous relative order
+    for key in keys {
+        if let Some(nt) = by_key.remove(&key) {
+            ordered.push(nt);
+        }
+    }
+    ordered
+}
+
+fn visit_type(
+    schema_type: &SchemaType,
+    by_key: &mut HashMap<String, NestedType>,
+    ordered: &mut Vec<NestedType>,
+) {
+    match schema_type {
+        SchemaType::Object(obj) => {
+            visit_fields(obj, by_key, ordered);
+            if let Some(nt) = by_key.remove(&type_key(obj)) {
+                ordered.push(nt);
+            }
+        }
+        SchemaType::Array(inner) | SchemaType::Optional(inner) | SchemaType::Map(inner) => {
+            visit_type(inner, by_key, ordered)
+        }
+        SchemaType::Union(types) | SchemaType::Tuple(types) => {
+            for t in types {
+                visit_type(t, by_key, ordered);
+            }
+        }
+        SchemaType::DiscriminatedUnion { variants, .. } => {
+            for variant in variants {
+                visit_type(&SchemaType::Object(variant.clone()), by_key, ordered);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn visit_fields(
+    obj: &ObjectType,
+    by_key: &mut HashMap<String, NestedType>,
+    ordered: &mut Vec<NestedType>,
+) {
+    for field in &obj.fields {
+        visit_type(&field.field_type, by_key, ordered);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{parse_json_string, parse_json_string_with_config, InferenceConfig};
+
+    fn names(schema: &Schema) -> Vec<&str> {
+        schema
+            .nested_types
+            .iter()
+            .map(|nt| nt.name.as_str())
+            .collect()
+    }
+
+    fn field_type<'a>(obj: &'a ObjectType, name: &str) -> &'a SchemaType {
+        &obj.fields
+            .iter()
+            .find(|f| f.name == name)
+            .unwrap()
+            .field_type
+    }
+
+    #[test]
+    fn test_identical_shapes_share_one_type() {
+        let json = r#"{
+            "billingAddress": {"street": "1 Main St", "city": "Oslo"},
+            "shippingAddress": {"street": "2 Side St", "city": "Bergen"}
+        }"#;
+        let schema = parse_json_string(json, "Order", false).unwrap();
+
+        assert_eq!(names(&schema), vec!["Address"]);
+        assert_eq!(
+            schema.nested_types[0].aliases,
+            vec!["OrderBillingAddress", "OrderShippingAddress"]
+        );
+        let root = schema.root_object().unwrap();
+        assert_eq!(
+            field_type(root, "billingAddress"),
+            field_type(root, "shippingAddress")
+        );
+    }
+
+    #[test]
+    fn test_subset_shapes_merge_with_optional_fields() {
+        let json = r#"{
+            "home": {"street": "1 Main St", "city": "Oslo", "zip": "0150"},
+            "work": {"street": "2 Side St", "city": "Bergen"}
+        }"#;
+        let schema = parse_json_string(json, "Person", false).unwrap();
+
+        assert_eq!(schema.nested_types.len(), 1);
+        let merged = &schema.nested_types[0].object;
+        let zip = merged.fields.iter().find(|f| f.name == "zip").unwrap();
+        assert!(zip.optional);
+        let street = merged.fields.iter().find(|f| f.name == "street").unwrap();
+        assert!(!street.optional);
+    }
+
+    #[test]
+    fn test_different_shapes_are_kept_apart() {
+        let json = r#"{
+            "author": {"name": "Ada", "email": "ada@example.com"},
+            "stats": {"views": 10, "likes": 2}
+        }"#;
+        let schema = parse_json_string(json, "Post", false).unwrap();
+
+        assert_eq!(names(&schema), vec!["PostAuthor", "PostStats"]);
+        assert!(schema.nested_types.iter().all(|nt| nt.aliases.is_empty()));
+    }
+
+    #[test]
+    fn test_single_shared_field_is_not_enough() {
+        let json = r#"{
+            "a": {"id": 1, "x": 1, "y": 2},
+            "b": {"id": 2}
+        }"#;
+        let schema = parse_json_string(json, "Root", false).unwrap();
+
+        assert_eq!(schema.nested_types.len(), 2);
+    }
+
+    #[test]
+    fn test_objects_inside_tuples_are_merged() {
+        let json = r#"{
+            "home": {"street": "1 Main St", "city": "Oslo", "zip": "0150"},
+            "stops": [
+                [1, [{"street": "2 Side St", "city": "Bergen"}]],
+                [2, [{"street": "3 Long St", "city": "Oslo"}]]
+            ]
+        }"#;
+        let schema = parse_json_string(json, "Person", false).unwrap();
+
+        assert_eq!(schema.nested_types.len(), 1);
+        let root = schema.root_object().unwrap();
+        let SchemaType::Array(stop) = field_type(root, "stops") else {
+            panic!("expected array");
+        };
+        let SchemaType::Tuple(positions) = stop.as_ref() else {
+            panic!("expected tuple, got {:?}", stop);
+        };
+        assert_eq!(
+            positions[1],
+            SchemaType::Array(Box::new(SchemaType::Object(
+                schema.nested_types[0].object.clone()
+            )))
+        );
+    }
+
+    #[test]
+    fn test_parents_collapse_after_children_merge() {
+        let json = r#"{
+            "sender": {"name": "A", "location": {"lat": 1.5, "lng": 2.5, "alt": 9.5}},
+            "receiver": {"name": "B", "location": {"lat": 3.5, "lng": 4.5}}
+        }"#;
+        let schema = parse_json_string(json, "Parcel", false).unwrap();
+
+        assert_eq!(names(&schema), vec!["Location", "ParcelReceiver"]);
+        assert_eq!(schema.nested_types[1].aliases, vec!["ParcelSender"]);
+    }
+
+    #[test]
+    fn test_falls_back_to_first_name_without_common_suffix() {
+        let json = r#"{
+            "owner": {"name": "A", "email": "a@example.com"},
+            "editor": {"name": "B", "email": "b@example.com"}
+        }"#;
+        let schema = parse_json_string(json, "Doc", false).unwrap();
+
+        assert_eq!(names(&schema), vec!["DocEditor"]);
+        assert_eq!(schema.nested_types[0].aliases, vec!["DocOwner"]);
+    }
+
+    #[test]
+    fn test_common_type_name() {
+        let names = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            common_type_name(&names(&["UserBillingAddress", "UserShippingAddress"])),
+            Some("Address".to_string())
+        );
+        assert_eq!(
+            common_type_name(&names(&["PostAuthorProfile", "CommentAuthorProfile"])),
+            Some("AuthorProfile".to_string())
+        );
+        assert_eq!(common_type_name(&names(&["CartItem", "OrderItem"])), None);
+        assert_eq!(common_type_name(&names(&["Owner", "Editor"])), None);
+    }
+
+    #[test]
+    fn test_flat_mode_skips_dedupe() {
+        let config = InferenceConfig {
+            flat_mode: true,
+            ..Default::default()
+        };
+        let json = r#"{"a": {"x": 1}, "b": {"x": 2}}"#;
+        let schema = parse_json_string_with_config(json, "Root", &config).unwrap();
+
+        assert!(schema.nested_types.is_empty());
+    }
+}
@@ -0,0 +1,261 @@
+use serde::Deserialize;
+
+use crate::types::{Field, ObjectType, SchemaType};
+use crate::utils::{to_pascal_case, to_snake_case};
+
+use super::stats::{SchemaStats, ARRAY_ITEM_SEGMENT, MAP_VALUE_SEGMENT};
+
+/// Field-name rules that fill in `FieldMetadata`. Names are compared in
+/// snake case, so `createdAt` and `created_at` are the same rule.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields, default)]
+pub struct NameHeuristics {
+    pub enabled: bool,
+    /// Fields whose values identify a record (`@unique` in Prisma)
+    pub unique: Vec<String>,
+    /// Fields set once by the server (`readonly` in TypeScript)
+    pub readonly: Vec<String>,
+    /// Mark `userId` / `user_id` as references to `User`
+    pub foreign_keys: bool,
+}
+
+impl Default for NameHeuristics {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            unique: ["id", "uuid", "slug", "email"].map(String::from).to_vec(),
+            readonly: ["id", "created_at"].map(String::from).to_vec(),
+            foreign_keys: true,
+        }
+    }
+}
+
+impl NameHeuristics {
+    fn matches(names: &[String], field_name: &str) -> bool {
+        let name = to_snake_case(field_name);
+        names.iter().any(|n| to_snake_case(n) == name)
+    }
+
+    /// `path` is where the field's values were recorded in `stats`; a name
+    /// that suggests uniqueness is ignored once a value repeats there
+    fn apply(&self, field: &mut Field, path: &[String], stats: &SchemaStats) {
+        // Map values are recorded under each of their keys, so whether one
+        // repeats across the map can't be told
+        let repeated = path.iter().any(|segment| segment == MAP_VALUE_SEGMENT)
+            || stats.get(path).is_some_and(|s| s.has_repeated_value());
+        if Self::matches(&self.unique, &field.name) && !repeated {
+            field.metadata.is_unique = true;
+        }
+        if Self::matches(&self.readonly, &field.name) {
+            field.metadata.is_readonly = true;
+        }
+        if self.foreign_keys {
+            field.metadata.references = referenced_model(&field.name);
+        }
+    }
+}
+
+/// `userId` / `user_id` → `User`
+fn referenced_model(field_name: &str) -> Option<String> {
+    let snake = to_snake_case(field_name);
+    let model = snake.strip_suffix("_id")?;
+    if model.is_empty() {
+        None
+    } else {
+        Some(to_pascal_case(model))
+    }
+}
+
+/// Set unique, readonly and foreign-key metadata on every field in
+/// `schema_type` whose name matches a heuristic. `stats` vetoes unique
+/// names whose values repeat in the samples.
+pub fn apply_name_heuristics(
+    schema_type: SchemaType,
+    heuristics: &NameHeuristics,
+    stats: &SchemaStats,
+) -> SchemaType {
+    if !heuristics.enabled {
+        return schema_type;
+    }
+    annotate(schema_type, heuristics, &mut Vec::new(), stats)
+}
+
+fn annotate(
+    schema_type: SchemaType,
+    heuristics: &NameHeuristics,
+    path: &mut Vec<String>,
+    stats: &SchemaStats,
+) -> SchemaType {
+    match schema_type {
+        SchemaType::Object(obj) => {
+            SchemaType::Object(annotate_object(obj, heuristics, path, stats))
+        }
+        SchemaType::Array(inner) => {
+            path.push(ARRAY_ITEM_SEGMENT.to_string());
+            let inner = annotate(*inner, heuristics, path, stats);
+            path.pop();
+            SchemaType::Array(Box::new(inner))
+        }
+        SchemaType::Optional(inner) => {
+            SchemaType::Optional(Box::new(annotate(*inner, heuristics, path, stats)))
+        }
+        SchemaType::Map(inner) => {
+            path.push(MAP_VALUE_SEGMENT.to_string());
+            let inner = annotate(*inner, heuristics, path, stats);
+            path.pop();
+            SchemaType::Map(Box::new(inner))
+        }
+        SchemaType::Union(types) => SchemaType::Union(
+            types
+                .into_iter()
+                .map(|t| annotate(t, heuristics, path, stats))
+                .collect(),
+        ),
+        SchemaType::Tuple(types) => {
+            path.push(ARRAY_ITEM_SEGMENT.to_string());
+            let types = types
+                .into_iter()
+                .map(|t| annotate(t, heuristics, path, stats))
+                .collect();
+            path.pop();
+            SchemaType::Tuple(types)
+        }
+        SchemaType::DiscriminatedUnion { tag, variants } => SchemaType::DiscriminatedUnion {
+            tag,
+            variants: variants
+                .into_iter()
+                .map(|v| annotate_object(v, heuristics, path, stats))
+                .collect(),
+        },
+        other => other,
+    }
+}
+
+fn annotate_object(
+    obj: ObjectType,
+    heuristics: &NameHeuristics,
+    path: &mut Vec<String>,
+    stats: &SchemaStats,
+) -> ObjectType {
+    ObjectType::new(
+        obj.fields
+            .into_iter()
+            .map(|mut field| {
+                path.push(field.name.clone());
+                heuristics.apply(&mut field, path, stats);
+                field.field_type = annotate(field.field_type, heuristics, path, stats);
+                path.pop();
+                field
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{infer_type_flat, InferenceConfig, SchemaBuilder};
+    use serde_json::json;
+
+    fn annotated(value: serde_json::Value, heuristics: &NameHeuristics) -> ObjectType {
+        match apply_name_heuristics(infer_type_flat(&value), heuristics, &SchemaStats::new()) {
+            SchemaType::Object(obj) => obj,
+            other => panic!("expected object, got {:?}", other),
+        }
+    }
+
+    fn field<'a>(obj: &'a ObjectType, name: &str) -> &'a Field {
+        obj.fields.iter().find(|f| f.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_default_heuristics() {
+        let obj = annotated(
+            json!({"id": 1, "slug": "a", "createdAt": "2024-01-01", "userId": 2, "name": "x"}),
+            &NameHeuristics::default(),
+        );
+
+        assert!(field(&obj, "id").metadata.is_unique);
+        assert!(field(&obj, "id").metadata.is_readonly);
+        assert!(field(&obj, "slug").metadata.is_unique);
+        assert!(field(&obj, "createdAt").metadata.is_readonly);
+        assert_eq!(
+            field(&obj, "userId").metadata.references.as_deref(),
+            Some("User")
+        );
+        assert_eq!(field(&obj, "name").metadata, Default::default());
+    }
+
+    #[test]
+    fn test_nested_objects_are_annotated() {
+        let obj = annotated(
+            json!({"items": [{"product_id": 1, "email": "a@b.co"}]}),
+            &NameHeuristics::default(),
+        );
+        let SchemaType::Array(inner) = &field(&obj, "items").field_type else {
+            panic!("expected array");
+        };
+        let SchemaType::Object(item) = inner.as_ref() else {
+            panic!("expected object");
+        };
+        assert_eq!(
+            field(item, "product_id").metadata.references.as_deref(),
+            Some("Product")
+        );
+        assert!(field(item, "email").metadata.is_unique);
+    }
+
+    #[test]
+    fn test_repeated_values_are_not_unique() {
+        let mut builder = SchemaBuilder::new("User", InferenceConfig::default());
+        builder.add_sample(&json!({"id": 1, "email": "a@b.co", "slug": "ada"}));
+        builder.add_sample(&json!({"id": 1, "email": "a@b.co", "slug": "grace"}));
+        let schema = builder.finish().unwrap();
+        let obj = schema.root_object().unwrap();
+
+        assert!(!field(obj, "id").metadata.is_unique);
+        assert!(field(obj, "id").metadata.is_readonly);
+        assert!(!field(obj, "email").metadata.is_unique);
+        assert!(field(obj, "slug").metadata.is_unique);
+    }
+
+    #[test]
+    fn test_map_values_are_not_unique() {
+        let mut builder = SchemaBuilder::new("Root", InferenceConfig::default());
+        builder.add_sample(&json!({"users": {
+            "101": {"email": "a@b.co"},
+            "102": {"email": "a@b.co"},
+            "103": {"email": "c@d.co"}
+        }}));
+        let schema = builder.finish().unwrap();
+        let SchemaType::Map(value) = &field(schema.root_object().unwrap(), "users").field_type
+        else {
+            panic!("expected map");
+        };
+        let SchemaType::Object(user) = value.as_ref() else {
+            panic!("expected object");
+        };
+
+        assert!(!field(user, "email").metadata.is_unique);
+    }
+
+    #[test]
+    fn test_custom_and_disabled_heuristics() {
+        let custom = NameHeuristics {
+            unique: vec!["sku".to_string()],
+            foreign_keys: false,
+            ..Default::default()
+        };
+        let obj = annotated(json!({"sku": "A-1", "id": 1, "userId": 2}), &custom);
+        assert!(field(&obj, "sku").metadata.is_unique);
+        assert!(!field(&obj, "id").metadata.is_unique);
+        assert_eq!(field(&obj, "userId").metadata.references, None);
+
+        let off = NameHeuristics {
+            enabled: false,
+            ..Default::default()
+        };
+        let obj = annotated(json!({"id": 1}), &off);
+        assert!(!field(&obj, "id").metadata.is_unique);
+    }
+}
@@ -1,108 +1,608 @@
-use crate::types::{Field, NestedType, ObjectType, SchemaType};
-use crate::utils::path_to_type_name;
+use crate::types::{Constraints, Field, NestedType, ObjectType, SchemaType};
+use crate::utils::{path_to_type_name, to_pascal_case, to_snake_case};
 use serde_json::Value;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
-use super::patterns::{detect_string_type, is_semantic_string_type};
+use super::patterns::{is_data_like_key, is_semantic_string_type, StringPatterns};
+use super::stats::{
+    path_key, whole_number, SchemaStats, ARRAY_ITEM_SEGMENT, MAP_VALUE_SEGMENT, MAX_TUPLE_LEN,
+};
+use super::InferenceConfig;
+
+/// Keys whose string value usually decides the rest of an object's shape
+pub const TAG_FIELDS: &[&str] = &["type", "kind", "event", "__typename"];
+
+/// ID-, number- or date-like keys an object may have before the rest of its
+/// values are folded into one map value type
+pub const MAX_OBJECT_KEYS: usize = 256;
 
 pub fn infer_type_flat(value: &Value) -> SchemaType {
+    infer_type(
+        value,
+        &mut Vec::new(),
+        &mut SchemaStats::new(),
+        &StringPatterns::default(),
+    )
+}
+
+/// Infer the type of `value`, recording what was observed at each path in `stats`
+pub fn infer_type(
+    value: &Value,
+    path: &mut Vec<String>,
+    stats: &mut SchemaStats,
+    patterns: &StringPatterns,
+) -> SchemaType {
+    stats.record(path, value);
     match value {
         Value::Null => SchemaType::Null,
         Value::Bool(_) => SchemaType::Boolean,
         Value::Number(n) => {
-            if n.is_i64() || n.is_u64() {
-                if let Some(f) = n.as_f64() {
-                    if f.fract() == 0.0 {
-                        return SchemaType::Integer;
-                    }
-                }
+            if whole_number(n).is_some() {
+                return SchemaType::Integer;
             }
             SchemaType::Number
         }
-        Value::String(s) => detect_string_type(s),
+        Value::String(s) => patterns.detect(s),
         Value::Array(arr) => {
             if arr.is_empty() {
+                stats.record_array(path, &[]);
                 SchemaType::Array(Box::new(SchemaType::Any))
             } else {
-                let types: Vec<SchemaType> = arr.iter().map(infer_type_flat).collect();
+                path.push(ARRAY_ITEM_SEGMENT.to_string());
+                let types: Vec<SchemaType> =
+                    arr.iter().map(|val| infer_type(val, path, stats, patterns)).collect();
+                path.pop();
+                stats.record_array(path, &types);
                 let unified = unify_types(&types);
                 SchemaType::Array(Box::new(unified))
             }
         }
         Value::Object(obj) => {
-            let fields: Vec<Field> = obj
-                .iter()
-                .map(|(key, val)| Field::new(key.clone(), infer_type_flat(val)))
-                .collect();
-            SchemaType::Object(ObjectType::new(fields))
+            let mut object = ObjectInference::new(path);
+            for (key, val) in obj {
+                path.push(object.segment(key));
+                let field_type = infer_type(val, path, stats, patterns);
+                path.pop();
+                object.add(key, field_type, val.as_str());
+            }
+            object.finish(path, stats)
         }
     }
 }
 
-pub fn infer_type_with_extraction(
-    value: &Value,
-    path: &mut Vec<String>,
-    nested_types: &mut Vec<NestedType>,
-) -> SchemaType {
-    match value {
-        Value::Null => SchemaType::Null,
-        Value::Bool(_) => SchemaType::Boolean,
-        Value::Number(n) => {
-            if n.is_i64() || n.is_u64() {
-                if let Some(f) = n.as_f64() {
-                    if f.fract() == 0.0 {
-                        return SchemaType::Integer;
-                    }
+/// Element types unified at a time while an array is built up
+const UNIFY_BATCH: usize = 256;
+
+/// An array's type, built up one element at a time without keeping the
+/// elements. The result is the same as inferring the whole array at once.
+#[derive(Default)]
+pub struct ArrayInference {
+    element: Option<SchemaType>,
+    /// Element types not yet unified into `element`
+    pending: Vec<SchemaType>,
+    /// Element types while the array is short enough to be a tuple
+    first: Vec<SchemaType>,
+    len: usize,
+}
+
+impl ArrayInference {
+    /// Add the type of the next element, inferred under [`ARRAY_ITEM_SEGMENT`]
+    pub fn add(&mut self, element_type: SchemaType) {
+        if self.len < MAX_TUPLE_LEN {
+            self.first.push(element_type.clone());
+        }
+        self.pending.push(element_type);
+        self.len += 1;
+        if self.pending.len() == UNIFY_BATCH {
+            self.unify_pending();
+        }
+    }
+
+    fn unify_pending(&mut self) {
+        let mut types: Vec<SchemaType> = self.element.take().into_iter().collect();
+        types.append(&mut self.pending);
+        self.element = Some(unify_types(&types));
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Record the array's length and positions at `path` and return its type
+    pub fn finish(mut self, path: &[String], stats: &mut SchemaStats) -> SchemaType {
+        if !self.pending.is_empty() {
+            self.unify_pending();
+        }
+        if self.len <= MAX_TUPLE_LEN {
+            stats.record_array(path, &self.first);
+        } else {
+            stats.record_array_len(path, self.len);
+        }
+        SchemaType::Array(Box::new(self.element.unwrap_or(SchemaType::Any)))
+    }
+}
+
+/// An object's type, built up one entry at a time. Once an object below the
+/// root has more than [`MAX_OBJECT_KEYS`] keys, all ID-, number- or
+/// date-like, it becomes a map: its further values are inferred under
+/// [`MAP_VALUE_SEGMENT`] and unified as they arrive, so a huge ID-keyed
+/// object keeps neither a field nor stats per key.
+pub struct ObjectInference {
+    fields: Vec<Field>,
+    /// String values of the tag fields seen so far
+    tags: Vec<(&'static str, String)>,
+    data_like_keys: bool,
+    /// Unified type of the values folded into a map so far
+    folded: Option<SchemaType>,
+}
+
+impl ObjectInference {
+    pub fn new(path: &[String]) -> Self {
+        let is_root = path.iter().all(|segment| segment == ARRAY_ITEM_SEGMENT);
+        Self {
+            fields: Vec::new(),
+            tags: Vec::new(),
+            data_like_keys: !is_root,
+            folded: None,
+        }
+    }
+
+    /// The path segment the value of `key` is inferred under
+    pub fn segment(&mut self, key: &str) -> String {
+        self.data_like_keys = self.data_like_keys && is_data_like_key(key);
+        if self.folding() {
+            MAP_VALUE_SEGMENT.to_string()
+        } else {
+            key.to_string()
+        }
+    }
+
+    /// Add the value of `key`, inferred under [`segment`](Self::segment).
+    /// `value` is the raw value if it was a string.
+    pub fn add(&mut self, key: &str, field_type: SchemaType, value: Option<&str>) {
+        if self.folding() {
+            self.folded = Some(match self.folded.take() {
+                Some(folded) => unify_types(&[folded, field_type]),
+                None => field_type,
+            });
+            return;
+        }
+        if let (Some(tag), Some(value)) = (TAG_FIELDS.iter().find(|t| **t == key), value) {
+            self.tags.push((tag, value.to_string()));
+        }
+        self.fields.push(Field::new(key, field_type));
+    }
+
+    fn folding(&self) -> bool {
+        self.folded.is_some() || (self.data_like_keys && self.fields.len() >= MAX_OBJECT_KEYS)
+    }
+
+    /// The object's type. A tagged object is also recorded at `path` under
+    /// each of its tag values, so differently shaped objects can be split
+    /// into a discriminated union once every sample has been seen.
+    pub fn finish(self, path: &[String], stats: &mut SchemaStats) -> SchemaType {
+        match self.folded {
+            None => {
+                let obj = ObjectType::new(self.fields);
+                for (tag, value) in &self.tags {
+                    stats.record_tagged(path, tag, value, &obj);
                 }
+                SchemaType::Object(obj)
+            }
+            Some(folded) => {
+                let mut values: Vec<SchemaType> =
+                    self.fields.into_iter().map(|f| f.field_type).collect();
+                values.push(folded);
+                SchemaType::Map(Box::new(unify_types(&values)))
             }
-            SchemaType::Number
         }
-        Value::String(s) => detect_string_type(s),
-        Value::Array(arr) => {
-            if arr.is_empty() {
-                SchemaType::Array(Box::new(SchemaType::Any))
-            } else {
-                let types: Vec<SchemaType> = arr
+    }
+}
+
+/// Apply the statistics-driven refinements enabled in `config` to an inferred type
+pub fn refine_types(
+    schema_type: SchemaType,
+    path: &mut Vec<String>,
+    config: &InferenceConfig,
+    stats: &SchemaStats,
+) -> SchemaType {
+    match schema_type {
+        SchemaType::String if config.detect_enums => match enum_values(path, config, stats) {
+            Some(values) => SchemaType::Enum(values),
+            None => SchemaType::String,
+        },
+        SchemaType::Integer => match epoch_unit(path, stats) {
+            Some(epoch) if config.detect_timestamps => epoch,
+            _ => integer_width(path, stats),
+        },
+        // Only tag literals are enums before refinement
+        SchemaType::Enum(_) => match enum_values(path, config, stats) {
+            Some(values) if config.detect_enums => SchemaType::Enum(values),
+            _ => SchemaType::String,
+        },
+        SchemaType::NumericString => numeric_string_width(path, stats),
+        SchemaType::Array(inner) => {
+            let positions = tuple_positions(path, stats);
+            path.push(ARRAY_ITEM_SEGMENT.to_string());
+            let refined = match positions {
+                Some(positions) => SchemaType::Tuple(
+                    positions
+                        .into_iter()
+                        .map(|t| refine_types(t, path, config, stats))
+                        .collect(),
+                ),
+                None => SchemaType::Array(Box::new(refine_types(*inner, path, config, stats))),
+            };
+            path.pop();
+            refined
+        }
+        SchemaType::Optional(inner) => {
+            SchemaType::Optional(Box::new(refine_types(*inner, path, config, stats)))
+        }
+        SchemaType::Union(types) => SchemaType::Union(
+            types
+                .into_iter()
+                .map(|t| refine_types(t, path, config, stats))
+                .collect(),
+        ),
+        SchemaType::Object(obj) => {
+            if let Some((tag, variants)) = tagged_variants(path, config, stats) {
+                let variants = variants
                     .iter()
-                    .map(|val| {
-                        if val.is_object() {
-                            path.push("Item".to_string());
-                            let t = infer_type_with_extraction(val, path, nested_types);
-                            path.pop();
-                            t
-                        } else {
-                            infer_type_with_extraction(val, path, nested_types)
+                    .map(|(value, variant)| {
+                        let mut variant = variant.clone();
+                        // Each variant's tag is the literal that selects it
+                        for field in &mut variant.fields {
+                            if field.name == tag {
+                                field.field_type = SchemaType::Enum(vec![value.clone()]);
+                            }
                         }
+                        refine_fields(variant, path, config, stats, Some(tag))
                     })
                     .collect();
-                let unified = unify_types(&types);
-                SchemaType::Array(Box::new(unified))
+                return SchemaType::DiscriminatedUnion {
+                    tag: tag.to_string(),
+                    variants,
+                };
+            }
+            let obj = refine_fields(obj, path, config, stats, None);
+            // The root (or the element of a root array) has to stay an object
+            let is_root = path.iter().all(|segment| segment == ARRAY_ITEM_SEGMENT);
+            if is_root {
+                SchemaType::Object(obj)
+            } else if config.map_paths.contains(&path_key(path)) {
+                SchemaType::Map(Box::new(map_value_type(&obj)))
+            } else {
+                detect_map(&obj, config).unwrap_or(SchemaType::Object(obj))
             }
         }
-        Value::Object(obj) => {
-            let fields: Vec<Field> = obj
-                .iter()
-                .map(|(key, val)| {
-                    let field_type = if val.is_object() && !val.as_object().unwrap().is_empty() {
-                        path.push(key.clone());
-                        let nested_type = infer_type_with_extraction(val, path, nested_types);
-
-                        if let SchemaType::Object(ref obj_type) = nested_type {
-                            let type_name = path_to_type_name(path);
-                            nested_types.push(NestedType::new(type_name.clone(), obj_type.clone()));
-                            path.pop();
-                            return Field::new(key.clone(), SchemaType::Object(obj_type.clone()));
-                        }
-                        path.pop();
-                        nested_type
-                    } else {
-                        infer_type_with_extraction(val, path, nested_types)
-                    };
-                    Field::new(key.clone(), field_type)
-                })
-                .collect();
-            SchemaType::Object(ObjectType::new(fields))
+        SchemaType::Map(inner) => {
+            path.push(MAP_VALUE_SEGMENT.to_string());
+            let refined = refine_types(*inner, path, config, stats);
+            path.pop();
+            SchemaType::Map(Box::new(refined))
+        }
+        other => other,
+    }
+}
+
+/// The tag field and per-value objects to split the objects at `path` into
+/// a discriminated union by: the first tag field every object there held a
+/// string in, if it held more than one value and the objects differ in more
+/// than their tag
+fn tagged_variants<'a>(
+    path: &[String],
+    config: &InferenceConfig,
+    stats: &'a SchemaStats,
+) -> Option<(&'static str, &'a [(String, ObjectType)])> {
+    if !config.discriminated_unions {
+        return None;
+    }
+    let path_stats = stats.get(path)?;
+    let (tag, tagged) = TAG_FIELDS.iter().find_map(|tag| {
+        let tagged = path_stats.tagged.get(tag)?;
+        (tagged.count == path_stats.object_count).then_some((*tag, tagged))
+    })?;
+    let variants = tagged.variants.as_slice();
+    if tagged.overflow || variants.len() < 2 || same_shapes(variants, tag) {
+        return None;
+    }
+    Some((tag, variants))
+}
+
+/// Widen a numeric string to fit the range observed at `path`
+fn numeric_string_width(path: &[String], stats: &SchemaStats) -> SchemaType {
+    match stats.get(path).and_then(|s| s.numeric_string_range) {
+        Some((min, max)) if min < i32::MIN.into() || max > i32::MAX.into() => {
+            SchemaType::WideNumericString
+        }
+        _ => SchemaType::NumericString,
+    }
+}
+
+/// Largest integer JavaScript can represent exactly (`Number.MAX_SAFE_INTEGER`)
+const MAX_SAFE_INTEGER: i128 = (1 << 53) - 1;
+
+/// Widen an integer to fit the range observed at `path`
+fn integer_width(path: &[String], stats: &SchemaStats) -> SchemaType {
+    match stats.get(path).and_then(|s| s.int_range) {
+        Some((min, max)) if min < -MAX_SAFE_INTEGER || max > MAX_SAFE_INTEGER => {
+            SchemaType::BigInt
+        }
+        Some((min, max)) if min < i32::MIN.into() || max > i32::MAX.into() => SchemaType::Int64,
+        _ => SchemaType::Integer,
+    }
+}
+
+/// Last words of field names that hold timestamps (`created_at`, `expiresAt`)
+const TIMESTAMP_WORDS: &[&str] = &[
+    "at", "timestamp", "ts", "time", "date", "created", "updated", "modified", "expires",
+    "expiry", "expiration",
+];
+
+/// 2000-01-01 and 2100-01-01 in Unix seconds, the range accepted as timestamps
+const EPOCH_SECONDS_RANGE: (i128, i128) = (946_684_800, 4_102_444_800);
+
+/// Unix timestamp type for integers named like a timestamp, by magnitude
+fn epoch_unit(path: &[String], stats: &SchemaStats) -> Option<SchemaType> {
+    let name = path.iter().rev().find(|s| *s != ARRAY_ITEM_SEGMENT)?;
+    let snake = to_snake_case(name);
+    let last_word = snake.rsplit('_').next()?;
+    if !TIMESTAMP_WORDS.contains(&last_word) {
+        return None;
+    }
+
+    let (min, max) = stats.get(path)?.int_range?;
+    let (low, high) = EPOCH_SECONDS_RANGE;
+    if min >= low && max < high {
+        Some(SchemaType::EpochSeconds)
+    } else if min >= low * 1000 && max < high * 1000 {
+        Some(SchemaType::EpochMillis)
+    } else {
+        None
+    }
+}
+
+/// Refine every field of `obj` except `keep` (a union variant's tag literal)
+fn refine_fields(
+    obj: ObjectType,
+    path: &mut Vec<String>,
+    config: &InferenceConfig,
+    stats: &SchemaStats,
+    keep: Option<&str>,
+) -> ObjectType {
+    let fields = obj
+        .fields
+        .into_iter()
+        .map(|mut field| {
+            if keep != Some(field.name.as_str()) {
+                path.push(field.name.clone());
+                field.field_type = refine_types(field.field_type, path, config, stats);
+                // A field that was only ever `null` could hold anything
+                if field.field_type == SchemaType::Null {
+                    field.field_type = SchemaType::Any;
+                    field.nullable = true;
+                }
+                if config.infer_constraints {
+                    field.metadata.constraints =
+                        observed_constraints(path, &field.field_type, stats);
+                }
+                path.pop();
+            }
+            field
+        })
+        .collect();
+    ObjectType::new(fields)
+}
+
+/// Bounds seen at `path` that make sense for a field of `field_type`: value
+/// ranges for numbers, lengths for plain strings and sizes for arrays
+fn observed_constraints(
+    path: &[String],
+    field_type: &SchemaType,
+    stats: &SchemaStats,
+) -> Constraints {
+    let mut constraints = Constraints::default();
+    let Some(path_stats) = stats.get(path) else {
+        return constraints;
+    };
+    match field_type {
+        SchemaType::Integer | SchemaType::Int64 | SchemaType::Number => {
+            if let Some((min, max)) = path_stats.number_range {
+                constraints.minimum = Some(min);
+                constraints.maximum = Some(max);
+            }
+        }
+        SchemaType::String => {
+            if let Some((min, max)) = path_stats.string_length {
+                constraints.min_length = Some(min);
+                constraints.max_length = Some(max);
+            }
+        }
+        SchemaType::Array(_) => {
+            if let Some((min, max)) = path_stats.array_length {
+                constraints.min_items = Some(min);
+                constraints.max_items = Some(max);
+            }
+        }
+        _ => {}
+    }
+    constraints
+}
+
+/// Position types for the array at `path` if it should be a tuple: seen at
+/// least twice, always with the same short length, and with one non-object
+/// type per position. Same-typed strings or booleans (`["a", "b"]`) read as
+/// lists rather than tuples, so only numbers (coordinates, ranges) qualify
+/// when every position has the same type. A single array is never a tuple,
+/// however it looks: one `[12.5, 41.9]` or `[1, "two", true]` says nothing
+/// about the length of the next.
+fn tuple_positions(path: &[String], stats: &SchemaStats) -> Option<Vec<SchemaType>> {
+    let path_stats = stats.get(path)?;
+    let positions = path_stats.positions.as_ref()?;
+    if path_stats.array_count < 2 || positions.len() < 2 {
+        return None;
+    }
+    let stable = positions.iter().all(|t| {
+        !matches!(
+            t,
+            SchemaType::Union(_) | SchemaType::Object(_) | SchemaType::Any
+        )
+    });
+    let homogeneous = positions.iter().all(|t| *t == positions[0]);
+    let numeric = numeric_width(&positions[0]).is_some();
+    if !stable || (homogeneous && !numeric) {
+        return None;
+    }
+    Some(positions.clone())
+}
+
+/// Treat `obj` as a dictionary if it has enough keys, every key looks like
+/// data (an ID, number or date) and all values share one type
+fn detect_map(obj: &ObjectType, config: &InferenceConfig) -> Option<SchemaType> {
+    if obj.fields.len() < config.map_min_keys.max(1)
+        || !obj.fields.iter().all(|f| is_data_like_key(&f.name))
+    {
+        return None;
+    }
+    let value_type = map_value_type(obj);
+    match split_nullable(value_type.clone()).0 {
+        SchemaType::Union(_) | SchemaType::Any => None,
+        _ => Some(SchemaType::Map(Box::new(value_type))),
+    }
+}
+
+/// The unified type of every value in `obj`, including `null` if any was null
+fn map_value_type(obj: &ObjectType) -> SchemaType {
+    let mut types: Vec<SchemaType> = obj.fields.iter().map(|f| f.field_type.clone()).collect();
+    if obj.fields.iter().any(|f| f.nullable) {
+        types.push(SchemaType::Null);
+    }
+    unify_types(&types)
+}
+
+/// The sorted set of values seen at `path` if it is small and repetitive
+/// enough to be treated as an enum
+fn enum_values(path: &[String], config: &InferenceConfig, stats: &SchemaStats) -> Option<Vec<String>> {
+    let path_stats = stats.get(path)?;
+    let distinct = path_stats.distinct_strings()?;
+    if distinct == 0
+        || distinct > config.enum_max_values
+        || path_stats.string_count < config.enum_min_occurrences
+        || distinct >= path_stats.string_count
+    {
+        return None;
+    }
+
+    let mut values: Vec<String> = path_stats.string_values.keys().cloned().collect();
+    values.sort();
+    Some(values)
+}
+
+pub fn infer_type_with_extraction(
+    value: &Value,
+    path: &mut Vec<String>,
+    nested_types: &mut Vec<NestedType>,
+) -> SchemaType {
+    let inferred = infer_type_flat(value);
+    extract_nested_types(&inferred, path, nested_types);
+    inferred
+}
+
+/// Register every non-empty object below the root type as a named nested
+/// type. Objects reached through a field are named after the field path,
+/// array elements get an `Item` suffix.
+pub fn extract_nested_types(
+    root_type: &SchemaType,
+    path: &mut Vec<String>,
+    nested_types: &mut Vec<NestedType>,
+) {
+    match root_type {
+        SchemaType::Object(obj) => extract_fields(obj, path, nested_types),
+        other => extract_from_type(other, path, nested_types),
+    }
+}
+
+fn extract_from_type(
+    schema_type: &SchemaType,
+    path: &mut Vec<String>,
+    nested_types: &mut Vec<NestedType>,
+) {
+    match schema_type {
+        SchemaType::Object(obj) if !obj.fields.is_empty() => {
+            extract_fields(obj, path, nested_types);
+            nested_types.push(NestedType::new(path_to_type_name(path), obj.clone()));
+        }
+        SchemaType::Array(inner) => {
+            path.push("Item".to_string());
+            extract_from_type(inner, path, nested_types);
+            path.pop();
+        }
+        SchemaType::Map(inner) => {
+            path.push("Value".to_string());
+            extract_from_type(inner, path, nested_types);
+            path.pop();
+        }
+        SchemaType::Optional(inner) => extract_from_type(inner, path, nested_types),
+        SchemaType::Union(types) => {
+            for t in types {
+                extract_from_type(t, path, nested_types);
+            }
         }
+        // Positions are numbered, e.g. `RouteLegItem2`
+        SchemaType::Tuple(types) => {
+            for (position, t) in types.iter().enumerate() {
+                path.push(tuple_item_segment(position));
+                extract_from_type(t, path, nested_types);
+                path.pop();
+            }
+        }
+        // Variants are named after their tag value, e.g. `EventClick`
+        SchemaType::DiscriminatedUnion { tag, variants } => {
+            for (variant, segment) in variants.iter().zip(variant_segments(tag, variants)) {
+                path.push(segment);
+                extract_from_type(&SchemaType::Object(variant.clone()), path, nested_types);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The path segment the type at `position` of a tuple is named by
+pub fn tuple_item_segment(position: usize) -> String {
+    format!("Item{}", position + 1)
+}
+
+/// The path segment each variant of a discriminated union is named by: its
+/// tag value, with a numeric suffix when two values give the same type name
+/// (`user_created` and `user-created`)
+pub fn variant_segments(tag: &str, variants: &[ObjectType]) -> Vec<String> {
+    let mut taken = HashSet::new();
+    variants
+        .iter()
+        .map(|variant| {
+            let value = variant.literal_value(tag).unwrap_or("Variant");
+            let mut segment = value.to_string();
+            let mut n = 2;
+            while !taken.insert(to_pascal_case(&segment)) {
+                segment = format!("{}{}", value, n);
+                n += 1;
+            }
+            segment
+        })
+        .collect()
+}
+
+fn extract_fields(obj: &ObjectType, path: &mut Vec<String>, nested_types: &mut Vec<NestedType>) {
+    for field in &obj.fields {
+        path.push(field.name.clone());
+        extract_from_type(&field.field_type, path, nested_types);
+        path.pop();
     }
 }
 
@@ -111,6 +611,9 @@ pub fn unify_types(types: &[SchemaType]) -> SchemaType {
         return SchemaType::Any;
     }
 
+    let merged = merge_structures(types);
+    let types = merged.as_slice();
+
     let unique: Vec<&SchemaType> = {
         let mut seen = HashSet::new();
         types
@@ -126,28 +629,305 @@ pub fn unify_types(types: &[SchemaType]) -> SchemaType {
         return unique[0].clone();
     }
 
-    let has_integer = unique.iter().any(|t| matches!(t, SchemaType::Integer));
-    let has_number = unique.iter().any(|t| matches!(t, SchemaType::Number));
-    if has_integer && has_number {
-        let filtered: Vec<SchemaType> = unique
+    // Numbers widen to the widest kind seen and differently formatted strings
+    // fall back to `String`. Each group collapses on its own, where it first
+    // appears, so unifying in steps (like the batches of a streamed array)
+    // gives the same result as unifying everything at once.
+    let numbers: Vec<&SchemaType> = unique
+        .iter()
+        .copied()
+        .filter(|t| numeric_width(t).is_some())
+        .collect();
+    let strings: Vec<&SchemaType> = unique.iter().copied().filter(|t| is_string_like(t)).collect();
+    let mut result = Vec::new();
+    let (mut numbers_done, mut strings_done) = (false, false);
+    for t in unique {
+        if numeric_width(t).is_some() {
+            if !numbers_done {
+                let widest = numbers.iter().max_by_key(|t| numeric_width(t)).unwrap();
+                result.push((*widest).clone());
+                numbers_done = true;
+            }
+        } else if is_string_like(t) {
+            if !strings_done {
+                result.push(join_strings(&strings));
+                strings_done = true;
+            }
+        } else {
+            result.push(t.clone());
+        }
+    }
+
+    if result.len() == 1 {
+        result.pop().unwrap()
+    } else {
+        SchemaType::Union(result)
+    }
+}
+
+fn is_string_like(schema_type: &SchemaType) -> bool {
+    matches!(schema_type, SchemaType::String | SchemaType::Enum(_))
+        || is_semantic_string_type(schema_type)
+}
+
+/// One type for strings seen in different formats
+fn join_strings(strings: &[&SchemaType]) -> SchemaType {
+    match strings {
+        [single] => (*single).clone(),
+        _ if strings
             .iter()
-            .filter(|t| !matches!(t, SchemaType::Integer))
-            .map(|t| (*t).clone())
-            .collect();
-        if filtered.len() == 1 {
-            return filtered[0].clone();
+            .all(|t| matches!(t, SchemaType::NumericString | SchemaType::WideNumericString)) =>
+        {
+            SchemaType::WideNumericString
+        }
+        // `"42"` next to `"19.99"` is a decimal string that happened to be whole
+        _ if strings.iter().all(|t| {
+            matches!(
+                t,
+                SchemaType::NumericString
+                    | SchemaType::WideNumericString
+                    | SchemaType::DecimalString
+            )
+        }) =>
+        {
+            SchemaType::DecimalString
+        }
+        _ => SchemaType::String,
+    }
+}
+
+/// Rank of the numeric types from narrowest to widest
+fn numeric_width(schema_type: &SchemaType) -> Option<u8> {
+    match schema_type {
+        SchemaType::Integer => Some(0),
+        SchemaType::Int64 => Some(1),
+        SchemaType::BigInt => Some(2),
+        SchemaType::Number => Some(3),
+        _ => None,
+    }
+}
+
+/// Flatten nested unions and fold every object (and every array, every map,
+/// and every enum) in `types` into a single merged entry, keeping the position of the
+/// first occurrence.
+fn merge_structures(types: &[SchemaType]) -> Vec<SchemaType> {
+    let mut flat: Vec<&SchemaType> = Vec::new();
+    for t in types {
+        match t {
+            SchemaType::Union(inner) => flat.extend(inner.iter()),
+            other => flat.push(other),
         }
-        return SchemaType::Union(filtered);
     }
 
-    let all_string_like = unique
+    let objects: Vec<&ObjectType> = flat
+        .iter()
+        .flat_map(|t| match t {
+            SchemaType::Object(obj) => vec![obj],
+            SchemaType::DiscriminatedUnion { variants, .. } => variants.iter().collect(),
+            _ => vec![],
+        })
+        .collect();
+    let array_items: Vec<SchemaType> = flat
+        .iter()
+        .filter_map(|t| match t {
+            SchemaType::Array(inner) if !matches!(**inner, SchemaType::Any) => {
+                Some((**inner).clone())
+            }
+            _ => None,
+        })
+        .collect();
+
+    let map_values: Vec<SchemaType> = flat
+        .iter()
+        .filter_map(|t| match t {
+            SchemaType::Map(inner) => Some((**inner).clone()),
+            _ => None,
+        })
+        .collect();
+
+    let enum_values: Vec<&String> = flat
         .iter()
-        .all(|t| matches!(t, SchemaType::String) || is_semantic_string_type(t));
-    if all_string_like && unique.len() > 1 {
-        return SchemaType::String;
+        .filter_map(|t| match t {
+            SchemaType::Enum(values) => Some(values),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+    let enum_count = flat.iter().filter(|t| matches!(t, SchemaType::Enum(_))).count();
+
+    let mut result = Vec::new();
+    let mut object_done = false;
+    let mut array_done = false;
+    let mut enum_done = false;
+    let mut map_done = false;
+    for t in flat {
+        match t {
+            // A map seen next to plain objects (e.g. a sample with only a
+            // couple of keys) absorbs them: their values join the map's
+            SchemaType::Map(_) | SchemaType::Object(_) | SchemaType::DiscriminatedUnion { .. }
+                if !map_values.is_empty() =>
+            {
+                if !map_done {
+                    let mut values = map_values.clone();
+                    for obj in &objects {
+                        values.extend(obj.fields.iter().map(|f| f.field_type.clone()));
+                    }
+                    result.push(SchemaType::Map(Box::new(unify_types(&values))));
+                    map_done = true;
+                }
+            }
+            SchemaType::Enum(_) if enum_count > 1 => {
+                if !enum_done {
+                    let mut values: Vec<String> = enum_values.iter().map(|v| (*v).clone()).collect();
+                    values.sort();
+                    values.dedup();
+                    result.push(SchemaType::Enum(values));
+                    enum_done = true;
+                }
+            }
+            SchemaType::Object(_) | SchemaType::DiscriminatedUnion { .. }
+                if objects.len() > 1 =>
+            {
+                if !object_done {
+                    result.push(merge_object_shapes(&objects));
+                    object_done = true;
+                }
+            }
+            SchemaType::Array(_) => {
+                if !array_done {
+                    let inner = if array_items.is_empty() {
+                        SchemaType::Any
+                    } else {
+                        unify_types(&array_items)
+                    };
+                    result.push(SchemaType::Array(Box::new(inner)));
+                    array_done = true;
+                }
+            }
+            other => result.push(other.clone()),
+        }
     }
+    result
+}
+
+/// Merge objects into one type, or into a discriminated union when they
+/// share a literal tag field with more than one value. Raw samples have no
+/// literals yet: they are split by the tag values recorded in the stats when
+/// the type is refined, so the result doesn't depend on the order (or
+/// batches) in which objects are unified.
+fn merge_object_shapes(objects: &[&ObjectType]) -> SchemaType {
+    if let Some(tag) = common_tag(objects) {
+        let mut groups: Vec<(&str, Vec<&ObjectType>)> = Vec::new();
+        for obj in objects {
+            let value = obj.literal_value(tag).unwrap_or_default();
+            match groups.iter_mut().find(|(v, _)| *v == value) {
+                Some((_, group)) => group.push(obj),
+                None => groups.push((value, vec![obj])),
+            }
+        }
 
-    SchemaType::Union(unique.iter().map(|t| (*t).clone()).collect())
+        if groups.len() > 1 {
+            return SchemaType::DiscriminatedUnion {
+                tag: tag.to_string(),
+                variants: groups.iter().map(|(_, group)| merge_objects(group)).collect(),
+            };
+        }
+    }
+    SchemaType::Object(merge_objects(objects))
+}
+
+/// The first tag field every object has, as a required string literal
+fn common_tag<'a>(objects: &[&ObjectType]) -> Option<&'a str> {
+    TAG_FIELDS.iter().copied().find(|tag| {
+        objects.iter().all(|obj| {
+            obj.fields
+                .iter()
+                .any(|f| f.name == *tag && !f.optional && !f.nullable)
+                && obj.literal_value(tag).is_some()
+        })
+    })
+}
+
+/// Whether the variants only differ in their tag
+fn same_shapes(variants: &[(String, ObjectType)], tag: &str) -> bool {
+    let first = shape_without(&variants[0].1, tag);
+    variants[1..].iter().all(|(_, v)| shape_without(v, tag) == first)
+}
+
+/// Fields other than `tag`, sorted by name so shapes compare regardless of
+/// key order (and of the values of other tag-like fields)
+fn shape_without<'a>(obj: &'a ObjectType, tag: &str) -> Vec<(&'a str, &'a SchemaType, bool, bool)> {
+    let mut fields: Vec<_> = obj
+        .fields
+        .iter()
+        .filter(|f| f.name != tag)
+        .map(|f| (f.name.as_str(), &f.field_type, f.optional, f.nullable))
+        .collect();
+    fields.sort_by(|a, b| a.0.cmp(b.0));
+    fields
+}
+
+/// Merge several object shapes into one. Every key seen in any object gets a
+/// field; keys missing from some of the objects are marked optional.
+pub fn merge_objects(objects: &[&ObjectType]) -> ObjectType {
+    let mut order: Vec<&str> = Vec::new();
+    let mut by_name: HashMap<&str, Vec<&Field>> = HashMap::new();
+    for obj in objects {
+        for field in &obj.fields {
+            by_name
+                .entry(field.name.as_str())
+                .or_insert_with(|| {
+                    order.push(field.name.as_str());
+                    Vec::new()
+                })
+                .push(field);
+        }
+    }
+
+    let fields = order
+        .into_iter()
+        .map(|name| {
+            let present = &by_name[name];
+            let mut types: Vec<SchemaType> =
+                present.iter().map(|f| f.field_type.clone()).collect();
+            if present.iter().any(|f| f.nullable) {
+                types.push(SchemaType::Null);
+            }
+            let (field_type, nullable) = split_nullable(unify_types(&types));
+            let mut metadata = present[0].metadata.clone();
+            metadata.constraints = present[1..].iter().fold(metadata.constraints, |c, f| {
+                c.widen(&f.metadata.constraints)
+            });
+            let mut field = Field::new(name, field_type).with_metadata(metadata);
+            field.optional =
+                present.len() < objects.len() || present.iter().any(|f| f.optional);
+            field.nullable = nullable;
+            field
+        })
+        .collect();
+
+    ObjectType::new(fields)
+}
+
+/// Separate `null` from a unified type: `string | null` becomes `(string, true)`.
+/// A type that is only ever `null` is kept as `Null`, since there is no
+/// underlying type to make nullable.
+pub fn split_nullable(schema_type: SchemaType) -> (SchemaType, bool) {
+    match schema_type {
+        SchemaType::Union(types) if types.contains(&SchemaType::Null) => {
+            let rest: Vec<SchemaType> = types
+                .into_iter()
+                .filter(|t| !matches!(t, SchemaType::Null))
+                .collect();
+            let inner = if rest.len() == 1 {
+                rest.into_iter().next().unwrap()
+            } else {
+                SchemaType::Union(rest)
+            };
+            (inner, true)
+        }
+        other => (other, false),
+    }
 }
 
 #[cfg(test)]
@@ -176,6 +956,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_unify_widens_integers() {
+        assert_eq!(
+            unify_types(&[SchemaType::Integer, SchemaType::Int64]),
+            SchemaType::Int64
+        );
+        assert_eq!(
+            unify_types(&[SchemaType::BigInt, SchemaType::Integer, SchemaType::Int64]),
+            SchemaType::BigInt
+        );
+        assert_eq!(
+            unify_types(&[SchemaType::BigInt, SchemaType::Number]),
+            SchemaType::Number
+        );
+    }
+
+    #[test]
+    fn test_unify_numeric_strings_widen_to_decimal() {
+        assert_eq!(
+            unify_types(&[SchemaType::NumericString, SchemaType::DecimalString]),
+            SchemaType::DecimalString
+        );
+        assert_eq!(
+            unify_types(&[SchemaType::NumericString, SchemaType::BooleanString]),
+            SchemaType::String
+        );
+    }
+
     #[test]
     fn test_unify_integer_and_number_collapses_to_number() {
         assert_eq!(
@@ -235,6 +1043,120 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_unify_merges_objects_with_different_keys() {
+        let a = SchemaType::Object(ObjectType::new(vec![
+            Field::new("id", SchemaType::Integer),
+            Field::new("name", SchemaType::String),
+        ]));
+        let b = SchemaType::Object(ObjectType::new(vec![
+            Field::new("id", SchemaType::Integer),
+            Field::new("email", SchemaType::Email),
+        ]));
+
+        let result = unify_types(&[a, b]);
+        if let SchemaType::Object(obj) = result {
+            let names: Vec<&str> = obj.fields.iter().map(|f| f.name.as_str()).collect();
+            assert_eq!(names, vec!["id", "name", "email"]);
+            assert!(!obj.fields[0].optional);
+            assert!(obj.fields[1].optional);
+            assert!(obj.fields[2].optional);
+        } else {
+            panic!("Expected merged Object type");
+        }
+    }
+
+    #[test]
+    fn test_unify_merges_arrays_ignoring_empty() {
+        let result = unify_types(&[
+            SchemaType::Array(Box::new(SchemaType::Any)),
+            SchemaType::Array(Box::new(SchemaType::String)),
+        ]);
+        assert_eq!(result, SchemaType::Array(Box::new(SchemaType::String)));
+    }
+
+    #[test]
+    fn test_merge_objects_keeps_optional_flag() {
+        let a = ObjectType::new(vec![Field::new("bio", SchemaType::String).optional()]);
+        let b = ObjectType::new(vec![Field::new("bio", SchemaType::String)]);
+
+        let merged = merge_objects(&[&a, &b]);
+        assert_eq!(merged.fields.len(), 1);
+        assert!(merged.fields[0].optional);
+    }
+
+    #[test]
+    fn test_merge_objects_unifies_field_types() {
+        let a = ObjectType::new(vec![Field::new("score", SchemaType::Integer)]);
+        let b = ObjectType::new(vec![Field::new("score", SchemaType::Number)]);
+
+        let merged = merge_objects(&[&a, &b]);
+        assert_eq!(merged.fields[0].field_type, SchemaType::Number);
+        assert!(!merged.fields[0].optional);
+    }
+
+    #[test]
+    fn test_split_nullable() {
+        assert_eq!(
+            split_nullable(SchemaType::Union(vec![SchemaType::String, SchemaType::Null])),
+            (SchemaType::String, true)
+        );
+        assert_eq!(split_nullable(SchemaType::Null), (SchemaType::Null, false));
+        assert_eq!(split_nullable(SchemaType::Integer), (SchemaType::Integer, false));
+
+        let (rest, nullable) = split_nullable(SchemaType::Union(vec![
+            SchemaType::String,
+            SchemaType::Null,
+            SchemaType::Boolean,
+        ]));
+        assert!(nullable);
+        assert_eq!(
+            rest,
+            SchemaType::Union(vec![SchemaType::String, SchemaType::Boolean])
+        );
+    }
+
+    #[test]
+    fn test_merge_objects_null_and_value_becomes_nullable() {
+        let a = ObjectType::new(vec![Field::new("bio", SchemaType::Null)]);
+        let b = ObjectType::new(vec![Field::new("bio", SchemaType::String)]);
+
+        let merged = merge_objects(&[&a, &b]);
+        assert_eq!(merged.fields[0].field_type, SchemaType::String);
+        assert!(merged.fields[0].nullable);
+        assert!(!merged.fields[0].optional);
+    }
+
+    #[test]
+    fn test_merge_objects_keeps_nullable_flag() {
+        let a = ObjectType::new(vec![Field::new("bio", SchemaType::String).nullable()]);
+        let b = ObjectType::new(vec![Field::new("bio", SchemaType::String)]);
+
+        let merged = merge_objects(&[&a, &b]);
+        assert_eq!(merged.fields[0].field_type, SchemaType::String);
+        assert!(merged.fields[0].nullable);
+    }
+
+    #[test]
+    fn test_unify_merges_enums() {
+        let result = unify_types(&[
+            SchemaType::Enum(vec!["b".to_string(), "a".to_string()]),
+            SchemaType::Enum(vec!["c".to_string(), "a".to_string()]),
+        ]);
+        assert_eq!(
+            result,
+            SchemaType::Enum(vec!["a".to_string(), "b".to_string(), "c".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_unify_enum_and_string_collapses_to_string() {
+        assert_eq!(
+            unify_types(&[SchemaType::Enum(vec!["a".to_string()]), SchemaType::String]),
+            SchemaType::String
+        );
+    }
+
     // --- infer_type_flat tests ---
 
     #[test]
@@ -257,6 +1179,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_infer_flat_number() {
         assert_eq!(infer_type_flat(&serde_json::json!(3.14)), SchemaType::Number);
         assert_eq!(infer_type_flat(&serde_json::json!(-0.5)), SchemaType::Number);
@@ -315,6 +1238,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_infer_flat_array_of_sparse_objects() {
+        let result = infer_type_flat(&serde_json::json!([
+            {"id": 1, "name": "a"},
+            {"id": 2},
+            {"id": 3, "tags": ["x"]}
+        ]));
+        if let SchemaType::Array(inner) = result {
+            if let SchemaType::Object(obj) = *inner {
+                assert_eq!(obj.fields.len(), 3);
+                let optional: Vec<(&str, bool)> = obj
+                    .fields
+                    .iter()
+                    .map(|f| (f.name.as_str(), f.optional))
+                    .collect();
+                assert_eq!(optional, vec![("id", false), ("name", true), ("tags", true)]);
+            } else {
+                panic!("Expected Object item type");
+            }
+        } else {
+            panic!("Expected Array type");
+        }
+    }
+
     // --- infer_type_with_extraction tests ---
 
     #[test]
@@ -379,4 +1326,565 @@ mod tests {
         // Path should be back to just ["Root"] after processing
         assert_eq!(path, vec!["Root".to_string()]);
     }
+
+    #[test]
+    fn test_extraction_names_tuple_positions() {
+        let tuple = SchemaType::Tuple(vec![
+            SchemaType::Integer,
+            SchemaType::Array(Box::new(SchemaType::Object(ObjectType::new(vec![
+                Field::new("stop", SchemaType::String),
+            ])))),
+        ]);
+        let mut nested = Vec::new();
+        extract_nested_types(&tuple, &mut vec!["Leg".to_string()], &mut nested);
+
+        assert_eq!(nested.len(), 1);
+        assert_eq!(nested[0].name, "LegItem2Item");
+    }
+
+    #[test]
+    fn test_extraction_names_array_items() {
+        let val = serde_json::json!({
+            "comments": [
+                {"id": 1, "text": "first"},
+                {"id": 2, "text": "second", "likes": 3}
+            ]
+        });
+        let mut path = vec!["Post".to_string()];
+        let mut nested = Vec::new();
+        infer_type_with_extraction(&val, &mut path, &mut nested);
+
+        assert_eq!(nested.len(), 1);
+        assert_eq!(nested[0].name, "PostCommentsItem");
+        assert_eq!(nested[0].object.fields.len(), 3);
+    }
+
+    // --- refine_types tests ---
+
+    fn refine(value: serde_json::Value, config: &InferenceConfig) -> SchemaType {
+        let mut stats = SchemaStats::new();
+        let inferred = infer_type(&value, &mut Vec::new(), &mut stats, &config.patterns);
+        refine_types(inferred, &mut Vec::new(), config, &stats)
+    }
+
+    fn enum_config() -> InferenceConfig {
+        InferenceConfig {
+            detect_enums: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_refine_detects_enum_across_array_elements() {
+        let val = serde_json::json!([
+            {"status": "active"},
+            {"status": "pending"},
+            {"status": "active"},
+            {"status": "active"}
+        ]);
+        let result = refine(val, &enum_config());
+        if let SchemaType::Array(inner) = result {
+            if let SchemaType::Object(obj) = *inner {
+                assert_eq!(
+                    obj.fields[0].field_type,
+                    SchemaType::Enum(vec!["active".to_string(), "pending".to_string()])
+                );
+            } else {
+                panic!("Expected Object item type");
+            }
+        } else {
+            panic!("Expected Array type");
+        }
+    }
+
+    #[test]
+    fn test_refine_enum_detection_is_opt_in() {
+        let val = serde_json::json!(["a", "b", "a", "b"]);
+        assert_eq!(
+            refine(val, &InferenceConfig::default()),
+            SchemaType::Array(Box::new(SchemaType::String))
+        );
+    }
+
+    #[test]
+    fn test_refine_skips_unique_values() {
+        let val = serde_json::json!(["alice", "bob", "carol", "dave"]);
+        assert_eq!(
+            refine(val, &enum_config()),
+            SchemaType::Array(Box::new(SchemaType::String))
+        );
+    }
+
+    #[test]
+    fn test_refine_respects_thresholds() {
+        let val = serde_json::json!(["a", "b", "c", "a", "b", "c"]);
+        let config = InferenceConfig {
+            enum_max_values: 2,
+            ..enum_config()
+        };
+        assert_eq!(
+            refine(val.clone(), &config),
+            SchemaType::Array(Box::new(SchemaType::String))
+        );
+
+        let config = InferenceConfig {
+            enum_min_occurrences: 10,
+            ..enum_config()
+        };
+        assert_eq!(
+            refine(val, &config),
+            SchemaType::Array(Box::new(SchemaType::String))
+        );
+    }
+
+    #[test]
+    fn test_refine_leaves_semantic_strings_alone() {
+        let val = serde_json::json!(["a@b.co", "a@b.co", "c@d.co"]);
+        assert_eq!(
+            refine(val, &enum_config()),
+            SchemaType::Array(Box::new(SchemaType::Email))
+        );
+    }
+
+    fn map_field(value: serde_json::Value, config: &InferenceConfig, name: &str) -> SchemaType {
+        match refine(value, config) {
+            SchemaType::Object(obj) => obj
+                .fields
+                .into_iter()
+                .find(|f| f.name == name)
+                .unwrap()
+                .field_type,
+            other => panic!("expected object, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_refine_detects_map_keyed_by_ids() {
+        let val = serde_json::json!({
+            "users": {
+                "u_123": {"name": "Ada", "age": 36},
+                "u_456": {"name": "Linus"},
+                "u_789": {"name": "Grace", "age": 85}
+            }
+        });
+        let users = map_field(val, &InferenceConfig::default(), "users");
+
+        let SchemaType::Map(value) = users else {
+            panic!("expected map, got {:?}", users);
+        };
+        let SchemaType::Object(obj) = *value else {
+            panic!("expected object values");
+        };
+        assert_eq!(obj.fields.len(), 2);
+        assert!(obj.fields.iter().find(|f| f.name == "age").unwrap().optional);
+    }
+
+    #[test]
+    fn test_refine_detects_map_keyed_by_dates() {
+        let val = serde_json::json!({
+            "visits": {"2024-01-01": 3, "2024-01-02": 5, "2024-01-03": 1}
+        });
+        assert_eq!(
+            map_field(val, &InferenceConfig::default(), "visits"),
+            SchemaType::Map(Box::new(SchemaType::Integer))
+        );
+    }
+
+    #[test]
+    fn test_refine_map_needs_enough_keys() {
+        let val = serde_json::json!({"scores": {"1": 10, "2": 20}});
+        assert!(matches!(
+            map_field(val, &InferenceConfig::default(), "scores"),
+            SchemaType::Object(_)
+        ));
+    }
+
+    #[test]
+    fn test_refine_map_needs_homogeneous_values() {
+        let val = serde_json::json!({"cells": {"1": 10, "2": "x", "3": true}});
+        assert!(matches!(
+            map_field(val, &InferenceConfig::default(), "cells"),
+            SchemaType::Object(_)
+        ));
+    }
+
+    #[test]
+    fn test_refine_ignores_regular_field_names() {
+        let val = serde_json::json!({"totals": {"net": 1, "tax": 2, "gross": 3}});
+        assert!(matches!(
+            map_field(val, &InferenceConfig::default(), "totals"),
+            SchemaType::Object(_)
+        ));
+    }
+
+    #[test]
+    fn test_refine_map_path_override() {
+        let config = InferenceConfig {
+            map_paths: vec!["data.totals".to_string()],
+            ..Default::default()
+        };
+        let val = serde_json::json!({"data": {"totals": {"net": 1, "tax": 2}}});
+        let SchemaType::Object(data) = map_field(val, &config, "data") else {
+            panic!("expected object");
+        };
+        assert_eq!(
+            data.fields[0].field_type,
+            SchemaType::Map(Box::new(SchemaType::Integer))
+        );
+    }
+
+    #[test]
+    fn test_huge_id_keyed_objects_are_folded_into_maps() {
+        let users: serde_json::Map<String, Value> = (0..300)
+            .map(|i| {
+                let role = if i % 2 == 0 { "admin" } else { "user" };
+                ((1000 + i).to_string(), serde_json::json!({"role": role}))
+            })
+            .collect();
+        let value = serde_json::json!({ "users": users });
+        let config = enum_config();
+        let mut stats = SchemaStats::new();
+        let inferred = infer_type(&value, &mut Vec::new(), &mut stats, &config.patterns);
+
+        let path = |segments: &[&str]| segments.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert!(stats.get(&path(&["users", "1000", "role"])).is_some());
+        assert!(stats.get(&path(&["users", "1299", "role"])).is_none());
+        assert_eq!(
+            stats
+                .get(&path(&["users", MAP_VALUE_SEGMENT, "role"]))
+                .unwrap()
+                .string_count,
+            300 - MAX_OBJECT_KEYS
+        );
+
+        let SchemaType::Object(root) = refine_types(inferred, &mut Vec::new(), &config, &stats)
+        else {
+            panic!("expected object");
+        };
+        let SchemaType::Map(user) = &root.fields[0].field_type else {
+            panic!("expected map, got {:?}", root.fields[0].field_type);
+        };
+        let SchemaType::Object(user) = user.as_ref() else {
+            panic!("expected object values");
+        };
+        assert_eq!(
+            user.fields[0].field_type,
+            SchemaType::Enum(vec!["admin".to_string(), "user".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_unify_map_absorbs_objects() {
+        let map = SchemaType::Map(Box::new(SchemaType::Integer));
+        let obj = SchemaType::Object(ObjectType::new(vec![Field::new("7", SchemaType::Number)]));
+        assert_eq!(
+            unify_types(&[map, obj]),
+            SchemaType::Map(Box::new(SchemaType::Number))
+        );
+    }
+
+    #[test]
+    fn test_unify_map_absorbs_discriminated_unions() {
+        let variant = |tag: &str, field: &str| {
+            ObjectType::new(vec![
+                Field::new("type", SchemaType::Enum(vec![tag.to_string()])),
+                Field::new(field, SchemaType::Integer),
+            ])
+        };
+        let map = SchemaType::Map(Box::new(SchemaType::Integer));
+        let union = SchemaType::DiscriminatedUnion {
+            tag: "type".to_string(),
+            variants: vec![variant("a", "x"), variant("b", "y")],
+        };
+        let SchemaType::Map(value) = unify_types(&[map, union]) else {
+            panic!("expected a single map");
+        };
+        assert_eq!(
+            *value,
+            unify_types(&[
+                SchemaType::Integer,
+                SchemaType::Enum(vec!["a".to_string(), "b".to_string()])
+            ])
+        );
+    }
+
+    #[test]
+    fn test_refine_detects_coordinate_tuples() {
+        let val = serde_json::json!([
+            {"at": [12.5, 41.9]},
+            {"at": [2.35, 48.85]}
+        ]);
+        let SchemaType::Array(inner) = refine(val, &InferenceConfig::default()) else {
+            panic!("expected array");
+        };
+        let SchemaType::Object(obj) = *inner else {
+            panic!("expected object");
+        };
+        assert_eq!(
+            obj.fields[0].field_type,
+            SchemaType::Tuple(vec![SchemaType::Number, SchemaType::Number])
+        );
+    }
+
+    #[test]
+    fn test_refine_detects_heterogeneous_pairs() {
+        let val = serde_json::json!({"pairs": [["a", 1], ["b", 2], ["c", 3]]});
+        assert_eq!(
+            map_field(val, &InferenceConfig::default(), "pairs"),
+            SchemaType::Array(Box::new(SchemaType::Tuple(vec![
+                SchemaType::String,
+                SchemaType::Integer
+            ])))
+        );
+    }
+
+    #[test]
+    fn test_refine_tuple_needs_two_observations() {
+        let val = serde_json::json!({"at": [12.5, 41.9]});
+        assert_eq!(
+            map_field(val, &InferenceConfig::default(), "at"),
+            SchemaType::Array(Box::new(SchemaType::Number))
+        );
+
+        let val = serde_json::json!({"city": ["Rome", 41.9]});
+        assert_eq!(
+            map_field(val, &InferenceConfig::default(), "city"),
+            SchemaType::Array(Box::new(SchemaType::Union(vec![
+                SchemaType::String,
+                SchemaType::Number
+            ])))
+        );
+    }
+
+    #[test]
+    fn test_refine_tuple_needs_stable_length_and_types() {
+        let lengths = serde_json::json!({"rows": [[1, 2], [1, 2, 3]]});
+        assert_eq!(
+            map_field(lengths, &InferenceConfig::default(), "rows"),
+            SchemaType::Array(Box::new(SchemaType::Array(Box::new(SchemaType::Integer))))
+        );
+
+        let types = serde_json::json!({"rows": [["a", 1], [2, "b"]]});
+        assert!(matches!(
+            map_field(types, &InferenceConfig::default(), "rows"),
+            SchemaType::Array(inner) if matches!(*inner, SchemaType::Array(_))
+        ));
+    }
+
+    #[test]
+    fn test_refine_same_typed_strings_stay_a_list() {
+        let val = serde_json::json!({"tags": [["a", "b"], ["c", "d"]]});
+        assert_eq!(
+            map_field(val, &InferenceConfig::default(), "tags"),
+            SchemaType::Array(Box::new(SchemaType::Array(Box::new(SchemaType::String))))
+        );
+    }
+
+    #[test]
+    fn test_refine_detects_discriminated_union() {
+        let val = serde_json::json!([
+            {"type": "click", "x": 1, "y": 2},
+            {"type": "view", "page": "/home"},
+            {"type": "click", "x": 3, "y": 4}
+        ]);
+        let refined = refine(val, &InferenceConfig::default());
+        let SchemaType::Array(inner) = refined else {
+            panic!("expected array, got {:?}", refined);
+        };
+        let SchemaType::DiscriminatedUnion { tag, variants } = *inner else {
+            panic!("expected discriminated union, got {:?}", inner);
+        };
+        assert_eq!(tag, "type");
+        assert_eq!(variants.len(), 2);
+        assert_eq!(variants[0].literal_value("type"), Some("click"));
+        assert_eq!(variants[1].literal_value("type"), Some("view"));
+        assert!(variants[0].fields.iter().all(|f| !f.optional));
+    }
+
+    #[test]
+    fn test_refine_same_shape_tags_stay_one_object() {
+        let val = serde_json::json!([
+            {"kind": "click", "at": 1},
+            {"kind": "view", "at": 2}
+        ]);
+        let refined = refine(val, &InferenceConfig::default());
+        let SchemaType::Array(inner) = refined else {
+            panic!("expected array, got {:?}", refined);
+        };
+        let SchemaType::Object(obj) = *inner else {
+            panic!("expected object, got {:?}", inner);
+        };
+        let kind = obj.fields.iter().find(|f| f.name == "kind").unwrap();
+        assert_eq!(kind.field_type, SchemaType::String);
+    }
+
+    #[test]
+    fn test_refine_single_tag_value_is_a_string() {
+        let val = serde_json::json!({"type": "user", "name": "Ada"});
+        let SchemaType::Object(obj) = refine(val, &InferenceConfig::default()) else {
+            panic!("expected object");
+        };
+        assert!(obj.fields.iter().all(|f| f.field_type == SchemaType::String));
+    }
+
+    #[test]
+    fn test_tag_fields_keep_detected_type() {
+        let mut stats = SchemaStats::new();
+        let value = serde_json::json!({"type": "a@b.co"});
+        let inferred = infer_type(&value, &mut Vec::new(), &mut stats, &Default::default());
+        let SchemaType::Object(obj) = inferred else {
+            panic!("expected object");
+        };
+        assert_eq!(obj.fields[0].field_type, SchemaType::Email);
+        let tagged = &stats.get(&[]).unwrap().tagged["type"];
+        assert_eq!(tagged.count, 1);
+        assert_eq!(tagged.variants[0].0, "a@b.co");
+    }
+
+    #[test]
+    fn test_refine_discriminated_unions_disabled() {
+        let val = serde_json::json!([
+            {"type": "click", "x": 1},
+            {"type": "view", "page": "/home"}
+        ]);
+        let config = InferenceConfig {
+            discriminated_unions: false,
+            ..InferenceConfig::default()
+        };
+        let SchemaType::Array(inner) = refine(val, &config) else {
+            panic!("expected array");
+        };
+        let SchemaType::Object(obj) = *inner else {
+            panic!("expected object, got {:?}", inner);
+        };
+        let names: Vec<&str> = obj.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["type", "x", "page"]);
+    }
+
+    #[test]
+    fn test_variant_segments_are_unique() {
+        let variant = |value: &str| {
+            ObjectType::new(vec![Field::new(
+                "type",
+                SchemaType::Enum(vec![value.to_string()]),
+            )])
+        };
+        let variants = vec![
+            variant("user_created"),
+            variant("user-created"),
+            variant("deleted"),
+        ];
+        assert_eq!(
+            variant_segments("type", &variants),
+            vec!["user_created", "user-created2", "deleted"]
+        );
+    }
+
+    #[test]
+    fn test_refine_integer_width_follows_observed_range() {
+        let val = serde_json::json!([
+            {"count": 12, "views": 3000000000_u64, "id": 1577836800123456789_u64},
+            {"count": -4, "views": 7, "id": 1}
+        ]);
+        let SchemaType::Array(inner) = refine(val, &InferenceConfig::default()) else {
+            panic!("expected array");
+        };
+        let SchemaType::Object(obj) = *inner else {
+            panic!("expected object");
+        };
+        let field_type = |name: &str| {
+            obj.fields
+                .iter()
+                .find(|f| f.name == name)
+                .unwrap()
+                .field_type
+                .clone()
+        };
+        assert_eq!(field_type("count"), SchemaType::Integer);
+        assert_eq!(field_type("views"), SchemaType::Int64);
+        assert_eq!(field_type("id"), SchemaType::BigInt);
+    }
+
+    #[test]
+    fn test_refine_detects_epoch_timestamps_by_name_and_magnitude() {
+        let val = serde_json::json!({
+            "created_at": 1697500000,
+            "expiresAt": 1697500000123_u64,
+            "timeout": 1697500000,
+            "response_time": 250
+        });
+        let config = InferenceConfig {
+            detect_timestamps: true,
+            ..Default::default()
+        };
+        let SchemaType::Object(obj) = refine(val, &config) else {
+            panic!("expected object");
+        };
+        let field_type = |name: &str| {
+            obj.fields
+                .iter()
+                .find(|f| f.name == name)
+                .unwrap()
+                .field_type
+                .clone()
+        };
+        assert_eq!(field_type("created_at"), SchemaType::EpochSeconds);
+        assert_eq!(field_type("expiresAt"), SchemaType::EpochMillis);
+        assert_eq!(field_type("timeout"), SchemaType::Integer);
+        assert_eq!(field_type("response_time"), SchemaType::Integer);
+    }
+
+    #[test]
+    fn test_refine_timestamps_are_opt_in() {
+        let val = serde_json::json!({"updated": 1697500000});
+        assert_eq!(
+            map_field(val, &InferenceConfig::default(), "updated"),
+            SchemaType::Integer
+        );
+    }
+
+    #[test]
+    fn test_refine_records_constraints_when_enabled() {
+        let val = serde_json::json!([
+            {"age": 31, "name": "Ann", "tags": ["a", "b"], "kind": true},
+            {"age": 7, "name": "Bartholomew", "tags": []}
+        ]);
+        let config = InferenceConfig {
+            infer_constraints: true,
+            ..Default::default()
+        };
+        let SchemaType::Array(inner) = refine(val.clone(), &config) else {
+            panic!("expected array");
+        };
+        let SchemaType::Object(obj) = *inner else {
+            panic!("expected object");
+        };
+        let constraints = |name: &str| {
+            obj.fields
+                .iter()
+                .find(|f| f.name == name)
+                .unwrap()
+                .metadata
+                .constraints
+                .clone()
+        };
+        assert_eq!(constraints("age").minimum, Some(7.0));
+        assert_eq!(constraints("age").maximum, Some(31.0));
+        assert_eq!(constraints("name").min_length, Some(3));
+        assert_eq!(constraints("name").max_length, Some(11));
+        assert_eq!(constraints("tags").min_items, Some(0));
+        assert_eq!(constraints("tags").max_items, Some(2));
+        assert_eq!(constraints("kind"), Constraints::default());
+
+        let SchemaType::Array(inner) = refine(val, &InferenceConfig::default()) else {
+            panic!("expected array");
+        };
+        let SchemaType::Object(obj) = *inner else {
+            panic!("expected object");
+        };
+        assert!(obj
+            .fields
+            .iter()
+            .all(|f| f.metadata.constraints == Constraints::default()));
+    }
 }
@@ -1,9 +1,10 @@
-use crate::types::{MoldError, Schema, SchemaType};
+use crate::types::Schema;
 use crate::utils::{get_file_stem, to_pascal_case};
 use anyhow::Result;
 use std::path::Path;
 
-use super::inference::{infer_type_flat, infer_type_with_extraction};
+use super::builder::SchemaBuilder;
+use super::InferenceConfig;
 
 pub fn parse_json_file(path: &Path, name: Option<&str>, flat_mode: bool) -> Result<Schema> {
     let content = std::fs::read_to_string(path)?;
@@ -15,30 +16,44 @@ pub fn parse_json_file(path: &Path, name: Option<&str>, flat_mode: bool) -> Resu
 }
 
 pub fn parse_json_string(json: &str, name: &str, flat_mode: bool) -> Result<Schema> {
+    let config = InferenceConfig {
+        flat_mode,
+        ..Default::default()
+    };
+    parse_json_string_with_config(json, name, &config)
+}
+
+pub fn parse_json_string_with_config(
+    json: &str,
+    name: &str,
+    config: &InferenceConfig,
+) -> Result<Schema> {
     let value: serde_json::Value = serde_json::from_str(json)?;
-    parse_json_value(&value, name, flat_mode)
+    parse_json_value_with_config(&value, name, config)
 }
 
 pub fn parse_json_value(value: &serde_json::Value, name: &str, flat_mode: bool) -> Result<Schema> {
-    let mut nested_types = Vec::new();
-    let mut path = vec![name.to_string()];
-
-    let root_type = if flat_mode {
-        infer_type_flat(value)
-    } else {
-        infer_type_with_extraction(value, &mut path, &mut nested_types)
+    let config = InferenceConfig {
+        flat_mode,
+        ..Default::default()
     };
+    parse_json_value_with_config(value, name, &config)
+}
 
-    if !matches!(root_type, SchemaType::Object(_)) {
-        return Err(MoldError::InvalidRoot(format!("{:?}", value)).into());
-    }
-
-    Ok(Schema::new(name, root_type).with_nested_types(nested_types))
+pub fn parse_json_value_with_config(
+    value: &serde_json::Value,
+    name: &str,
+    config: &InferenceConfig,
+) -> Result<Schema> {
+    let mut builder = SchemaBuilder::new(name, config.clone());
+    builder.add_sample(value);
+    builder.finish()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::SchemaType;
 
     #[test]
     fn test_parse_simple_object() {
@@ -0,0 +1,615 @@
+use crate::types::{MoldError, Schema};
+use anyhow::Result;
+use serde_json::{Map, Number, Value};
+
+use super::annotations::{Annotations, FieldAnnotation};
+use super::builder::SchemaBuilder;
+use super::stats::ARRAY_ITEM_SEGMENT;
+use super::InferenceConfig;
+
+/// Parse JSON5 / JSONC: plain JSON plus `//` and `/* */` comments, trailing
+/// commas, single-quoted strings, unquoted keys, hex numbers and numbers
+/// with a leading `+` or a bare decimal point. Errors carry the line and
+/// column they were found at.
+pub fn parse_lenient(input: &str) -> Result<Value, MoldError> {
+    Parser::new(input, None).parse_document()
+}
+
+/// Like [`parse_lenient`], also reading `@tags` from the comments next to
+/// object members. A comment on the line a member ends on belongs to that
+/// member; any other comment belongs to the member after it.
+pub fn parse_lenient_annotated(input: &str) -> Result<(Value, Annotations), MoldError> {
+    let mut parser = Parser::new(input, Some(Annotations::new()));
+    let value = parser.parse_document()?;
+    Ok((value, parser.annotations.unwrap_or_default()))
+}
+
+pub fn parse_lenient_string(input: &str, name: &str, config: &InferenceConfig) -> Result<Schema> {
+    let value = parse_lenient(input)?;
+    let mut builder = SchemaBuilder::new(name, config.clone());
+    builder.add_sample(&value);
+    builder.finish()
+}
+
+/// Deepest nesting of objects and arrays accepted, the same limit as
+/// serde_json's, so malicious input can't overflow the stack
+const MAX_DEPTH: usize = 128;
+
+/// A comment's text without its delimiters, and where that text starts
+struct Comment<'a> {
+    start: usize,
+    text: &'a str,
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+    /// Collected only when annotations are being read
+    annotations: Option<Annotations>,
+    /// Comments skipped since they were last claimed by a member
+    comments: Vec<Comment<'a>>,
+    /// Path of the value being parsed, as in inference
+    path: Vec<String>,
+    /// Objects and arrays currently open
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str, annotations: Option<Annotations>) -> Self {
+        Self {
+            input,
+            bytes: input.as_bytes(),
+            pos: 0,
+            annotations,
+            comments: Vec::new(),
+            path: Vec::new(),
+            depth: 0,
+        }
+    }
+
+    fn parse_document(&mut self) -> Result<Value, MoldError> {
+        // A byte order mark is not part of the document
+        if self.input.starts_with('\u{feff}') {
+            self.pos = '\u{feff}'.len_utf8();
+        }
+        let value = self.parse_value()?;
+        self.skip_whitespace()?;
+        if self.pos < self.bytes.len() {
+            return Err(self.error("unexpected content after the document"));
+        }
+        Ok(value)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error(&self, message: impl Into<String>) -> MoldError {
+        self.error_at(self.pos, message)
+    }
+
+    fn error_at(&self, pos: usize, message: impl Into<String>) -> MoldError {
+        let before = &self.input[..pos];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        MoldError::Syntax {
+            line,
+            column: before[line_start..].chars().count() + 1,
+            message: message.into(),
+        }
+    }
+
+    fn describe_next(&self) -> String {
+        match self.input[self.pos..].chars().next() {
+            Some(c) => format!("'{}'", c),
+            None => "end of input".to_string(),
+        }
+    }
+
+    fn skip_whitespace(&mut self) -> Result<(), MoldError> {
+        loop {
+            match self.peek() {
+                Some(b' ' | b'\t' | b'\n' | b'\r' | 0x0b | 0x0c) => self.pos += 1,
+                Some(b'/') if self.bytes.get(self.pos + 1) == Some(&b'/') => {
+                    let start = self.pos + 2;
+                    while !matches!(self.peek(), None | Some(b'\n')) {
+                        self.pos += 1;
+                    }
+                    self.record_comment(start, self.pos);
+                }
+                Some(b'/') if self.bytes.get(self.pos + 1) == Some(&b'*') => {
+                    let start = self.pos;
+                    match self.input[self.pos + 2..].find("*/") {
+                        Some(end) => {
+                            self.record_comment(start + 2, start + 2 + end);
+                            self.pos += end + 4;
+                        }
+                        None => return Err(self.error_at(start, "unterminated block comment")),
+                    }
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn record_comment(&mut self, start: usize, end: usize) {
+        if self.annotations.is_some() {
+            self.comments.push(Comment {
+                start,
+                text: &self.input[start..end],
+            });
+        }
+    }
+
+    /// Read the tags in `comments` into the annotations of member `key`
+    fn annotate(&mut self, key: &str, comments: Vec<Comment<'a>>) -> Result<(), MoldError> {
+        for comment in comments {
+            let annotation = FieldAnnotation::parse(comment.text)
+                .map_err(|e| self.error_at(comment.start + e.offset, e.message))?;
+            if let (Some(annotation), Some(annotations)) = (annotation, self.annotations.as_mut()) {
+                self.path.push(key.to_string());
+                annotations.insert(&self.path, annotation);
+                self.path.pop();
+            }
+        }
+        Ok(())
+    }
+
+    /// Hand the pending comments on the line where member `key` ended (at
+    /// `end`) to that member, keeping the rest pending
+    fn annotate_trailing(&mut self, key: &str, end: usize) -> Result<(), MoldError> {
+        let input = self.input;
+        let (trailing, rest) = std::mem::take(&mut self.comments)
+            .into_iter()
+            .partition(|c| !input[end..c.start].contains('\n'));
+        self.comments = rest;
+        self.annotate(key, trailing)
+    }
+
+    fn parse_value(&mut self) -> Result<Value, MoldError> {
+        self.skip_whitespace()?;
+        match self.peek() {
+            Some(open @ (b'{' | b'[')) => {
+                if self.depth == MAX_DEPTH {
+                    return Err(self.error("recursion limit exceeded"));
+                }
+                self.depth += 1;
+                let value = if open == b'{' {
+                    self.parse_object()
+                } else {
+                    self.parse_array()
+                };
+                self.depth -= 1;
+                value
+            }
+            Some(quote @ (b'"' | b'\'')) => self.parse_string(quote).map(Value::String),
+            Some(b'-' | b'+' | b'.' | b'0'..=b'9') => self.parse_number().map(Value::Number),
+            Some(b'a'..=b'z' | b'A'..=b'Z') => {
+                let start = self.pos;
+                match self.parse_identifier().as_str() {
+                    "true" => Ok(Value::Bool(true)),
+                    "false" => Ok(Value::Bool(false)),
+                    "null" => Ok(Value::Null),
+                    "Infinity" | "NaN" => {
+                        Err(self.error_at(start, "Infinity and NaN have no JSON equivalent"))
+                    }
+                    word => Err(self.error_at(start, format!("unexpected identifier '{}'", word))),
+                }
+            }
+            _ => Err(self.error(format!("expected a value, found {}", self.describe_next()))),
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Value, MoldError> {
+        self.pos += 1;
+        // Comments before the brace belong to whatever holds this object
+        self.comments.clear();
+        let mut map = Map::new();
+        // Key of the previous member and where its value ended
+        let mut previous: Option<(String, usize)> = None;
+        loop {
+            self.skip_whitespace()?;
+            if let Some((key, end)) = previous.take() {
+                self.annotate_trailing(&key, end)?;
+            }
+            if self.eat(b'}') {
+                self.comments.clear();
+                return Ok(Value::Object(map));
+            }
+            let key = match self.peek() {
+                Some(quote @ (b'"' | b'\'')) => self.parse_string(quote)?,
+                Some(b) if is_identifier_start(b) => self.parse_identifier(),
+                _ => {
+                    return Err(self.error(format!(
+                        "expected an object key, found {}",
+                        self.describe_next()
+                    )))
+                }
+            };
+            self.skip_whitespace()?;
+            if !self.eat(b':') {
+                return Err(self.error(format!(
+                    "expected ':' after key '{}', found {}",
+                    key,
+                    self.describe_next()
+                )));
+            }
+            self.skip_whitespace()?;
+            let leading = std::mem::take(&mut self.comments);
+            self.annotate(&key, leading)?;
+
+            self.path.push(key.clone());
+            let value = self.parse_value()?;
+            self.path.pop();
+            let end = self.pos;
+            map.insert(key.clone(), value);
+            self.skip_whitespace()?;
+            if !self.eat(b',') {
+                if self.eat(b'}') {
+                    self.annotate_trailing(&key, end)?;
+                    self.comments.clear();
+                    return Ok(Value::Object(map));
+                }
+                return Err(self.error(format!(
+                    "expected ',' or '}}' in object, found {}",
+                    self.describe_next()
+                )));
+            }
+            previous = Some((key, end));
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Value, MoldError> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        self.path.push(ARRAY_ITEM_SEGMENT.to_string());
+        loop {
+            self.skip_whitespace()?;
+            if self.eat(b']') {
+                break;
+            }
+            items.push(self.parse_value()?);
+            self.skip_whitespace()?;
+            if !self.eat(b',') {
+                if self.eat(b']') {
+                    break;
+                }
+                return Err(self.error(format!(
+                    "expected ',' or ']' in array, found {}",
+                    self.describe_next()
+                )));
+            }
+        }
+        self.path.pop();
+        // Comments between elements describe no member
+        self.comments.clear();
+        Ok(Value::Array(items))
+    }
+
+    fn parse_identifier(&mut self) -> String {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|b| is_identifier_start(b) || b.is_ascii_digit())
+        {
+            self.pos += 1;
+        }
+        self.input[start..self.pos].to_string()
+    }
+
+    fn parse_string(&mut self, quote: u8) -> Result<String, MoldError> {
+        let start = self.pos;
+        self.pos += 1;
+        let mut out = String::new();
+        loop {
+            let run_start = self.pos;
+            while !matches!(self.peek(), None | Some(b'\\' | b'\n' | b'\r'))
+                && self.peek() != Some(quote)
+            {
+                self.pos += 1;
+            }
+            out.push_str(&self.input[run_start..self.pos]);
+            match self.peek() {
+                Some(b'\\') => {
+                    self.pos += 1;
+                    self.parse_escape(&mut out)?;
+                }
+                Some(b) if b == quote => {
+                    self.pos += 1;
+                    return Ok(out);
+                }
+                _ => return Err(self.error_at(start, "unterminated string")),
+            }
+        }
+    }
+
+    fn parse_escape(&mut self, out: &mut String) -> Result<(), MoldError> {
+        let escape_pos = self.pos - 1;
+        let Some(b) = self.peek() else {
+            return Err(self.error("unterminated string"));
+        };
+        self.pos += 1;
+        match b {
+            b'n' => out.push('\n'),
+            b't' => out.push('\t'),
+            b'r' => out.push('\r'),
+            b'b' => out.push('\u{8}'),
+            b'f' => out.push('\u{c}'),
+            b'v' => out.push('\u{b}'),
+            b'0' => out.push('\0'),
+            // A backslash before a line break continues the string
+            b'\n' => {}
+            b'\r' => {
+                self.eat(b'\n');
+            }
+            b'x' => {
+                let code = self.parse_hex_digits(2, escape_pos)?;
+                out.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+            }
+            b'u' => {
+                let mut code = self.parse_hex_digits(4, escape_pos)?;
+                // A high surrogate combines with a following `\uDC00`-`\uDFFF`
+                let rest = &self.bytes[self.pos..];
+                if (0xd800..0xdc00).contains(&code) && rest.starts_with(b"\\u") {
+                    let low = std::str::from_utf8(&rest[2..rest.len().min(6)])
+                        .ok()
+                        .and_then(|d| u32::from_str_radix(d, 16).ok())
+                        .filter(|low| (0xdc00..0xe000).contains(low));
+                    if let Some(low) = low {
+                        self.pos += 6;
+                        code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                    }
+                }
+                out.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+            }
+            _ if b.is_ascii() => out.push(b as char),
+            _ => {
+                // Any other character escapes to itself
+                self.pos -= 1;
+                let c = self.input[self.pos..].chars().next().unwrap_or('\u{fffd}');
+                self.pos += c.len_utf8();
+                out.push(c);
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_hex_digits(&mut self, count: usize, escape_pos: usize) -> Result<u32, MoldError> {
+        let digits = self
+            .input
+            .get(self.pos..self.pos + count)
+            .filter(|d| d.bytes().all(|b| b.is_ascii_hexdigit()))
+            .ok_or_else(|| self.error_at(escape_pos, "invalid escape sequence"))?;
+        self.pos += count;
+        Ok(u32::from_str_radix(digits, 16).unwrap_or(0))
+    }
+
+    fn parse_number(&mut self) -> Result<Number, MoldError> {
+        let start = self.pos;
+        let negative = match self.peek() {
+            Some(b'-') => {
+                self.pos += 1;
+                true
+            }
+            Some(b'+') => {
+                self.pos += 1;
+                false
+            }
+            _ => false,
+        };
+        if self.peek().is_some_and(|b| b.is_ascii_alphabetic()) {
+            let word = self.parse_identifier();
+            return Err(match word.as_str() {
+                "Infinity" | "NaN" => {
+                    self.error_at(start, "Infinity and NaN have no JSON equivalent")
+                }
+                _ => self.error_at(start, "invalid number"),
+            });
+        }
+
+        let sign = if negative { "-" } else { "" };
+        let text = if self.input[self.pos..].starts_with("0x")
+            || self.input[self.pos..].starts_with("0X")
+        {
+            self.pos += 2;
+            let digits_start = self.pos;
+            while self.peek().is_some_and(|b| b.is_ascii_hexdigit()) {
+                self.pos += 1;
+            }
+            let value = u128::from_str_radix(&self.input[digits_start..self.pos], 16)
+                .map_err(|_| self.error_at(start, "invalid hexadecimal number"))?;
+            format!("{}{}", sign, value)
+        } else {
+            let digits_start = self.pos;
+            while self
+                .peek()
+                .is_some_and(|b| b.is_ascii_digit() || matches!(b, b'.' | b'e' | b'E'))
+                || (matches!(self.peek(), Some(b'+' | b'-'))
+                    && matches!(self.bytes[self.pos - 1], b'e' | b'E'))
+            {
+                self.pos += 1;
+            }
+            let digits = &self.input[digits_start..self.pos];
+            // JSON wants a digit on both sides of the decimal point
+            let digits = match digits.split_once('.') {
+                Some((int, frac)) => {
+                    let int = if int.is_empty() { "0" } else { int };
+                    match frac.chars().next() {
+                        Some(c) if c.is_ascii_digit() => format!("{}.{}", int, frac),
+                        _ => format!("{}.0{}", int, frac),
+                    }
+                }
+                None => digits.to_string(),
+            };
+            format!("{}{}", sign, digits)
+        };
+        text.parse::<Number>().map_err(|_| {
+            self.error_at(
+                start,
+                format!("invalid number '{}'", &self.input[start..self.pos]),
+            )
+        })
+    }
+}
+
+fn is_identifier_start(b: u8) -> bool {
+    b.is_ascii_alphabetic() || b == b'_' || b == b'$' || b >= 0x80
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn syntax_error(input: &str) -> (usize, usize, String) {
+        match parse_lenient(input) {
+            Err(MoldError::Syntax {
+                line,
+                column,
+                message,
+            }) => (line, column, message),
+            other => panic!("expected a syntax error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_plain_json_parses_like_serde() {
+        let input = r#"{"a": [1, -2.5e3, null], "b": {"c": "d\né\ud83d\ude00"}, "big": 12345678901234567890}"#;
+        let expected: Value = serde_json::from_str(input).unwrap();
+        assert_eq!(parse_lenient(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_comments_and_trailing_commas() {
+        let input = "// fixture\n{\n  /* the id */ \"id\": 1, // trailing\n  \"tags\": [\"a\", \"b\",],\n}\n";
+        assert_eq!(
+            parse_lenient(input).unwrap(),
+            json!({"id": 1, "tags": ["a", "b"]})
+        );
+    }
+
+    #[test]
+    fn test_json5_syntax() {
+        let input =
+            "{unquoted: 'single \\'quoted\\'', $key_2: 0x1F, lead: .5, trail: 5., plus: +3, \
+                     multi: 'a\\\nb', 'x': \"it's\"}";
+        let value = parse_lenient(input).unwrap();
+        assert_eq!(value["unquoted"], "single 'quoted'");
+        assert_eq!(value["$key_2"], 31);
+        assert_eq!(value["lead"].as_f64(), Some(0.5));
+        assert_eq!(value["trail"].as_f64(), Some(5.0));
+        assert_eq!(value["plus"], 3);
+        assert_eq!(value["multi"], "ab");
+        assert_eq!(value["x"], "it's");
+    }
+
+    #[test]
+    fn test_keeps_key_order() {
+        let value = parse_lenient("{b: 1, a: 2}").unwrap();
+        let keys: Vec<&String> = value.as_object().unwrap().keys().collect();
+        assert_eq!(keys, vec!["b", "a"]);
+    }
+
+    #[test]
+    fn test_errors_report_line_and_column() {
+        assert_eq!(
+            syntax_error("{\n  \"a\": 1\n  \"b\": 2\n}"),
+            (
+                3,
+                3,
+                "expected ',' or '}' in object, found '\"'".to_string()
+            )
+        );
+        assert_eq!(syntax_error("{a: 'oops\n}").0, 1);
+        assert_eq!(syntax_error("{a: 'oops\n}").1, 5);
+        assert_eq!(
+            syntax_error("[1, 2] x").2,
+            "unexpected content after the document"
+        );
+        assert_eq!(syntax_error("/* open").2, "unterminated block comment");
+        assert_eq!(
+            syntax_error("{a: NaN}").2,
+            "Infinity and NaN have no JSON equivalent"
+        );
+        assert_eq!(syntax_error("[,]").2, "expected a value, found ','");
+        assert_eq!(
+            syntax_error("{a 1}").2,
+            "expected ':' after key 'a', found '1'"
+        );
+    }
+
+    #[test]
+    fn test_nesting_limit() {
+        let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        assert!(parse_lenient(&nested(MAX_DEPTH)).is_ok());
+        let (line, column, message) = syntax_error(&nested(MAX_DEPTH + 1));
+        assert_eq!((line, column), (1, MAX_DEPTH + 1));
+        assert_eq!(message, "recursion limit exceeded");
+        // Far too deep to recurse through
+        assert!(parse_lenient(&"[".repeat(200_000)).is_err());
+    }
+
+    #[test]
+    fn test_annotations_attach_to_members() {
+        let input = r#"[
+  {
+    // @readonly
+    "id": 1,
+    "email": "a@b.co", // @unique @description Primary contact
+    "price": "9.99", /* @type Decimal */
+    // @optional
+    "nickname": "Al",
+    "address": { "zip": "A1" } // @nullable
+  },
+  { "id": 2, "note": "ops@example.com" } // plain
+]"#;
+        let (value, annotations) = parse_lenient_annotated(input).unwrap();
+        assert_eq!(value[1]["id"], 2);
+
+        assert!(annotations.get("[].id").unwrap().readonly);
+        let email = annotations.get("[].email").unwrap();
+        assert!(email.unique);
+        assert_eq!(email.description.as_deref(), Some("Primary contact"));
+        assert!(annotations.get("[].price").unwrap().field_type.is_some());
+        assert!(annotations.get("[].nickname").unwrap().optional);
+        assert!(annotations.get("[].address").unwrap().nullable);
+        assert_eq!(annotations.len(), 5);
+    }
+
+    #[test]
+    fn test_nested_annotations_and_last_member() {
+        let input = "{\n  user: {\n    // @unique\n    name: 'x' // @description Login\n  },\n}";
+        let (_, annotations) = parse_lenient_annotated(input).unwrap();
+        let name = annotations.get("user.name").unwrap();
+        assert!(name.unique);
+        assert_eq!(name.description.as_deref(), Some("Login"));
+        assert!(annotations.get("user").is_none());
+    }
+
+    #[test]
+    fn test_bad_annotation_reports_position() {
+        match parse_lenient_annotated("{\n  a: 1, // @uniq\n}") {
+            Err(MoldError::Syntax {
+                line,
+                column,
+                message,
+            }) => {
+                assert_eq!((line, column), (2, 12));
+                assert_eq!(message, "unknown annotation '@uniq'");
+            }
+            other => panic!("expected a syntax error, got {:?}", other),
+        }
+        // Without annotations, tags are just comments
+        assert!(parse_lenient("{\n  a: 1, // @uniq\n}").is_ok());
+    }
+}
@@ -1,5 +1,81 @@
+mod annotations;
+mod builder;
+mod csv;
+mod dedupe;
+mod heuristics;
 mod inference;
 mod json;
+mod lenient;
+mod ndjson;
+mod ordering;
 mod patterns;
+mod recursion;
+mod report;
+mod stats;
+mod stream;
+mod yaml;
 
-pub use json::{parse_json_file, parse_json_string, parse_json_value};
+use crate::types::KeyOrder;
+
+pub use annotations::{Annotations, FieldAnnotation};
+pub use builder::{ArraySample, SchemaBuilder};
+pub use csv::{parse_csv_string, read_csv_samples, CsvOptions};
+pub use heuristics::NameHeuristics;
+pub use inference::{infer_type_flat, infer_type_with_extraction, merge_objects, unify_types};
+pub use json::{
+    parse_json_file, parse_json_string, parse_json_string_with_config, parse_json_value,
+    parse_json_value_with_config,
+};
+pub use lenient::{parse_lenient, parse_lenient_annotated, parse_lenient_string};
+pub use patterns::{builtin_format_names, StringPatterns};
+pub use report::{PathReport, SchemaReport};
+pub use stats::{PathStats, SchemaStats};
+pub use stream::{parse_json_reader, read_json_stream, Sampling, StreamSummary};
+pub use yaml::{parse_yaml_documents, parse_yaml_string};
+pub use ndjson::{parse_ndjson_reader, parse_ndjson_string, read_ndjson_samples, MalformedRecord};
+
+#[derive(Debug, Clone)]
+pub struct InferenceConfig {
+    pub flat_mode: bool,
+    pub detect_enums: bool,
+    pub enum_max_values: usize,
+    pub enum_min_occurrences: usize,
+    /// Objects with at least this many ID-, number- or date-like keys and
+    /// values of one type are inferred as maps
+    pub map_min_keys: usize,
+    /// Paths (e.g. `data.users`) that are always treated as maps
+    pub map_paths: Vec<String>,
+    /// Integers in timestamp-named fields (`created_at`, `expires`) with
+    /// plausible magnitudes are inferred as Unix timestamps
+    pub detect_timestamps: bool,
+    /// Order of the fields within each type
+    pub key_order: KeyOrder,
+    /// Split arrays of objects whose `type`/`kind`/`event`/`__typename`
+    /// field selects a differently shaped variant into discriminated unions
+    pub discriminated_unions: bool,
+    /// Record observed value ranges, string lengths and array sizes on fields
+    pub infer_constraints: bool,
+    /// Field-name rules for unique, readonly and foreign-key metadata
+    pub heuristics: NameHeuristics,
+    /// String formats to detect, including user-defined ones
+    pub patterns: StringPatterns,
+}
+
+impl Default for InferenceConfig {
+    fn default() -> Self {
+        Self {
+            flat_mode: false,
+            detect_enums: false,
+            enum_max_values: 10,
+            enum_min_occurrences: 3,
+            map_min_keys: 3,
+            map_paths: Vec::new(),
+            detect_timestamps: false,
+            key_order: KeyOrder::default(),
+            discriminated_unions: true,
+            infer_constraints: false,
+            heuristics: NameHeuristics::default(),
+            patterns: StringPatterns::default(),
+        }
+    }
+}
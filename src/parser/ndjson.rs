@@ -0,0 +1,127 @@
+use crate::types::Schema;
+use anyhow::Result;
+use std::io::BufRead;
+
+use super::builder::SchemaBuilder;
+use super::InferenceConfig;
+
+/// A line of NDJSON input that could not be parsed and was skipped
+#[derive(Debug, Clone, PartialEq)]
+pub struct MalformedRecord {
+    pub line: usize,
+    pub message: String,
+}
+
+/// Feed every line of an NDJSON / JSON Lines stream into `builder` as one
+/// sample. Blank lines are ignored, and lines that aren't valid UTF-8 or
+/// valid JSON are skipped and reported instead of failing the whole read.
+pub fn read_ndjson_samples<R: BufRead>(
+    mut reader: R,
+    builder: &mut SchemaBuilder,
+) -> Result<Vec<MalformedRecord>> {
+    let mut malformed = Vec::new();
+    let mut buf = Vec::new();
+    let mut line_number = 0;
+    loop {
+        buf.clear();
+        if reader.read_until(b'\n', &mut buf)? == 0 {
+            break;
+        }
+        line_number += 1;
+        let line = match std::str::from_utf8(&buf) {
+            Ok(line) => line,
+            Err(e) => {
+                malformed.push(MalformedRecord {
+                    line: line_number,
+                    message: e.to_string(),
+                });
+                continue;
+            }
+        };
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<serde_json::Value>(trimmed) {
+            Ok(value) => builder.add_sample(&value),
+            Err(e) => malformed.push(MalformedRecord {
+                line: line_number,
+                message: e.to_string(),
+            }),
+        }
+    }
+    Ok(malformed)
+}
+
+pub fn parse_ndjson_reader<R: BufRead>(
+    reader: R,
+    name: &str,
+    config: &InferenceConfig,
+) -> Result<(Schema, Vec<MalformedRecord>)> {
+    let mut builder = SchemaBuilder::new(name, config.clone());
+    let malformed = read_ndjson_samples(reader, &mut builder)?;
+    Ok((builder.finish()?, malformed))
+}
+
+pub fn parse_ndjson_string(
+    input: &str,
+    name: &str,
+    config: &InferenceConfig,
+) -> Result<(Schema, Vec<MalformedRecord>)> {
+    parse_ndjson_reader(input.as_bytes(), name, config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SchemaType;
+
+    #[test]
+    fn test_lines_fold_into_one_schema() {
+        let input = "{\"id\": 1, \"type\": \"click\"}\n\n{\"id\": 2, \"x\": 4.5}\n";
+        let (schema, malformed) =
+            parse_ndjson_string(input, "Event", &InferenceConfig::default()).unwrap();
+
+        assert!(malformed.is_empty());
+        if let SchemaType::Object(obj) = &schema.root_type {
+            let names: Vec<&str> = obj.fields.iter().map(|f| f.name.as_str()).collect();
+            assert_eq!(names, vec!["id", "type", "x"]);
+            assert!(!obj.fields[0].optional);
+            assert!(obj.fields[1].optional);
+            assert!(obj.fields[2].optional);
+        } else {
+            panic!("Expected Object type");
+        }
+    }
+
+    #[test]
+    fn test_malformed_lines_are_reported() {
+        let input = "{\"id\": 1}\n{\"id\": \n{\"id\": 3}\nnot json\n";
+        let (schema, malformed) =
+            parse_ndjson_string(input, "Event", &InferenceConfig::default()).unwrap();
+
+        let lines: Vec<usize> = malformed.iter().map(|m| m.line).collect();
+        assert_eq!(lines, vec![2, 4]);
+        assert!(matches!(schema.root_type, SchemaType::Object(_)));
+    }
+
+    #[test]
+    fn test_non_utf8_lines_are_reported() {
+        let input: &[u8] = b"{\"id\": 1}\n{\"name\": \"caf\xe9\"}\r\n{\"id\": 3}\n";
+        let (schema, malformed) =
+            parse_ndjson_reader(input, "Event", &InferenceConfig::default()).unwrap();
+
+        assert_eq!(malformed.len(), 1);
+        assert_eq!(malformed[0].line, 2);
+        assert!(malformed[0].message.contains("utf-8"));
+        let obj = schema.root_object().unwrap();
+        assert_eq!(obj.fields.len(), 1);
+        assert!(!obj.fields[0].optional);
+    }
+
+    #[test]
+    fn test_all_lines_malformed_is_an_error() {
+        let result = parse_ndjson_string("nope\n", "Event", &InferenceConfig::default());
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,106 @@
+use crate::types::{KeyOrder, NestedType, ObjectType, Schema, SchemaType};
+
+/// Put the fields of every object in `schema` in `order`. The root and the
+/// nested types are rewritten the same way, so equal objects stay equal and
+/// the `{:?}`-keyed generator refs still match. Merging nested types can
+/// append fields or make them optional, so this runs again after dedupe.
+pub fn apply_key_order(schema: Schema, order: KeyOrder) -> Schema {
+    if order == KeyOrder::Source {
+        return schema;
+    }
+    let nested_types = schema
+        .nested_types
+        .into_iter()
+        .map(|nt| NestedType {
+            object: order_object(nt.object, order),
+            ..nt
+        })
+        .collect();
+    Schema {
+        root_type: order_type(schema.root_type, order),
+        nested_types,
+        ..schema
+    }
+}
+
+/// Put the fields of every object in `schema_type` in `order`
+pub fn order_type(schema_type: SchemaType, order: KeyOrder) -> SchemaType {
+    match schema_type {
+        SchemaType::Object(obj) => SchemaType::Object(order_object(obj, order)),
+        SchemaType::Array(inner) => SchemaType::Array(Box::new(order_type(*inner, order))),
+        SchemaType::Optional(inner) => SchemaType::Optional(Box::new(order_type(*inner, order))),
+        SchemaType::Map(inner) => SchemaType::Map(Box::new(order_type(*inner, order))),
+        SchemaType::Union(types) => {
+            SchemaType::Union(types.into_iter().map(|t| order_type(t, order)).collect())
+        }
+        SchemaType::Tuple(types) => {
+            SchemaType::Tuple(types.into_iter().map(|t| order_type(t, order)).collect())
+        }
+        SchemaType::DiscriminatedUnion { tag, variants } => SchemaType::DiscriminatedUnion {
+            tag,
+            variants: variants
+                .into_iter()
+                .map(|v| order_object(v, order))
+                .collect(),
+        },
+        other => other,
+    }
+}
+
+fn order_object(obj: ObjectType, order: KeyOrder) -> ObjectType {
+    let mut obj = ObjectType::new(
+        obj.fields
+            .into_iter()
+            .map(|mut field| {
+                field.field_type = order_type(field.field_type, order);
+                field
+            })
+            .collect(),
+    );
+    obj.sort_fields(order);
+    obj
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{parse_json_string_with_config, InferenceConfig};
+
+    const JSON: &str = r#"[
+        {"zip": "1", "name": "a", "address": {"street": "x", "city": "y"}},
+        {"name": "b", "id": 2, "address": {"street": "x", "city": "y"}}
+    ]"#;
+
+    fn field_names(order: KeyOrder) -> (Vec<String>, Vec<String>) {
+        let config = InferenceConfig {
+            key_order: order,
+            ..Default::default()
+        };
+        let schema = parse_json_string_with_config(JSON, "Rows", &config).unwrap();
+        let names = |obj: &ObjectType| obj.fields.iter().map(|f| f.name.clone()).collect();
+        (
+            names(schema.root_object().unwrap()),
+            names(&schema.nested_types[0].object),
+        )
+    }
+
+    #[test]
+    fn test_source_order() {
+        let (root, address) = field_names(KeyOrder::Source);
+        assert_eq!(root, vec!["zip", "name", "address", "id"]);
+        assert_eq!(address, vec!["street", "city"]);
+    }
+
+    #[test]
+    fn test_alphabetical_order() {
+        let (root, address) = field_names(KeyOrder::Alphabetical);
+        assert_eq!(root, vec!["address", "id", "name", "zip"]);
+        assert_eq!(address, vec!["city", "street"]);
+    }
+
+    #[test]
+    fn test_required_first_order() {
+        let (root, _) = field_names(KeyOrder::RequiredFirst);
+        assert_eq!(root, vec!["name", "address", "zip", "id"]);
+    }
+}
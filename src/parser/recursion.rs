@@ -0,0 +1,303 @@
+use crate::types::{Field, ObjectType, SchemaType};
+use crate::utils::path_to_type_name;
+
+use super::dedupe::is_compatible_by;
+use super::inference::{merge_objects, tuple_item_segment, variant_segments};
+
+/// Replace descendants that repeat an ancestor's shape with a `Ref` to it.
+///
+/// Each object is checked against its own subtree (top-down, so the outermost
+/// ancestor wins). The first match along a branch must carry the field the
+/// branch was entered through (a `replies` entry with its own `replies`);
+/// below that, any compatible object counts, so leaf nodes fold in too.
+/// Matches are merged back into the ancestor, which makes fields missing
+/// from some levels optional. `path` names the object like extraction does.
+pub fn fold_recursive_types(obj: &ObjectType, path: &mut Vec<String>) -> ObjectType {
+    let name = path_to_type_name(path);
+    let mut matches = Vec::new();
+    let replaced = ObjectType::new(
+        obj.fields
+            .iter()
+            .map(|field| Field {
+                field_type: replace_matches(
+                    &field.field_type,
+                    obj,
+                    &name,
+                    Some(&field.name),
+                    &mut matches,
+                ),
+                ..field.clone()
+            })
+            .collect(),
+    );
+
+    let folded = if matches.is_empty() {
+        replaced
+    } else {
+        let mut all = vec![&replaced];
+        all.extend(matches.iter());
+        merge_objects(&all)
+    };
+
+    ObjectType::new(
+        folded
+            .fields
+            .iter()
+            .map(|field| {
+                path.push(field.name.clone());
+                let field_type = fold_recursive_type(&field.field_type, path);
+                path.pop();
+                Field {
+                    field_type,
+                    ..field.clone()
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Fold every object within `schema_type`, naming them by `path`
+pub fn fold_recursive_type(schema_type: &SchemaType, path: &mut Vec<String>) -> SchemaType {
+    match schema_type {
+        SchemaType::Object(obj) if !obj.fields.is_empty() => {
+            SchemaType::Object(fold_recursive_types(obj, path))
+        }
+        SchemaType::Array(inner) => {
+            path.push("Item".to_string());
+            let inner = fold_recursive_type(inner, path);
+            path.pop();
+            SchemaType::Array(Box::new(inner))
+        }
+        SchemaType::Map(inner) => {
+            path.push("Value".to_string());
+            let inner = fold_recursive_type(inner, path);
+            path.pop();
+            SchemaType::Map(Box::new(inner))
+        }
+        SchemaType::Optional(inner) => {
+            SchemaType::Optional(Box::new(fold_recursive_type(inner, path)))
+        }
+        SchemaType::Union(types) => {
+            SchemaType::Union(types.iter().map(|t| fold_recursive_type(t, path)).collect())
+        }
+        SchemaType::Tuple(types) => SchemaType::Tuple(
+            types
+                .iter()
+                .enumerate()
+                .map(|(position, t)| {
+                    path.push(tuple_item_segment(position));
+                    let folded = fold_recursive_type(t, path);
+                    path.pop();
+                    folded
+                })
+                .collect(),
+        ),
+        SchemaType::DiscriminatedUnion { tag, variants } => SchemaType::DiscriminatedUnion {
+            tag: tag.clone(),
+            variants: variants
+                .iter()
+                .zip(variant_segments(tag, variants))
+                .map(|(variant, segment)| {
+                    path.push(segment);
+                    let folded = fold_recursive_types(variant, path);
+                    path.pop();
+                    folded
+                })
+                .collect(),
+        },
+        other => other.clone(),
+    }
+}
+
+fn replace_matches(
+    schema_type: &SchemaType,
+    ancestor: &ObjectType,
+    name: &str,
+    entry: Option<&str>,
+    matches: &mut Vec<ObjectType>,
+) -> SchemaType {
+    match schema_type {
+        SchemaType::Object(obj) => {
+            let is_match = is_compatible_by(ancestor, obj, same_shape)
+                && entry.is_none_or(|e| obj.fields.iter().any(|f| f.name == e));
+            let next_entry = if is_match { None } else { entry };
+            let replaced = ObjectType::new(
+                obj.fields
+                    .iter()
+                    .map(|field| Field {
+                        field_type: replace_matches(
+                            &field.field_type,
+                            ancestor,
+                            name,
+                            next_entry,
+                            matches,
+                        ),
+                        ..field.clone()
+                    })
+                    .collect(),
+            );
+            if is_match {
+                matches.push(replaced);
+                SchemaType::Ref(name.to_string())
+            } else {
+                SchemaType::Object(replaced)
+            }
+        }
+        SchemaType::Array(inner) => SchemaType::Array(Box::new(replace_matches(
+            inner, ancestor, name, entry, matches,
+        ))),
+        SchemaType::Optional(inner) => SchemaType::Optional(Box::new(replace_matches(
+            inner, ancestor, name, entry, matches,
+        ))),
+        SchemaType::Map(inner) => SchemaType::Map(Box::new(replace_matches(
+            inner, ancestor, name, entry, matches,
+        ))),
+        SchemaType::Union(types) => SchemaType::Union(
+            types
+                .iter()
+                .map(|t| replace_matches(t, ancestor, name, entry, matches))
+                .collect(),
+        ),
+        SchemaType::Tuple(types) => SchemaType::Tuple(
+            types
+                .iter()
+                .map(|t| replace_matches(t, ancestor, name, entry, matches))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Loose type equality for shape matching: nested structure is allowed to
+/// differ in depth, and `null` or an empty array fits anything
+fn same_shape(a: &SchemaType, b: &SchemaType) -> bool {
+    match (a, b) {
+        (SchemaType::Null, _) | (_, SchemaType::Null) => true,
+        (
+            SchemaType::Object(_) | SchemaType::Ref(_),
+            SchemaType::Object(_) | SchemaType::Ref(_),
+        ) => true,
+        (SchemaType::Array(x), SchemaType::Array(y)) => {
+            matches!(**x, SchemaType::Any) || matches!(**y, SchemaType::Any) || same_shape(x, y)
+        }
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::infer_type_flat;
+    use serde_json::json;
+
+    fn fold(value: serde_json::Value, name: &str) -> ObjectType {
+        match infer_type_flat(&value) {
+            SchemaType::Object(obj) => fold_recursive_types(&obj, &mut vec![name.to_string()]),
+            other => panic!("expected object, got {:?}", other),
+        }
+    }
+
+    fn field<'a>(obj: &'a ObjectType, name: &str) -> &'a Field {
+        obj.fields.iter().find(|f| f.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_comment_thread_folds_into_self_reference() {
+        let obj = fold(
+            json!({
+                "id": 1,
+                "text": "root",
+                "replies": [
+                    {"id": 2, "text": "a", "replies": [
+                        {"id": 3, "text": "b", "replies": []}
+                    ]}
+                ]
+            }),
+            "Comment",
+        );
+
+        assert_eq!(
+            field(&obj, "replies").field_type,
+            SchemaType::Array(Box::new(SchemaType::Ref("Comment".to_string())))
+        );
+        assert!(!field(&obj, "replies").optional);
+    }
+
+    #[test]
+    fn test_leaves_without_the_recursive_field_fold_in() {
+        let obj = fold(
+            json!({
+                "name": "root",
+                "size": 1,
+                "children": [
+                    {"name": "a", "size": 2, "children": [{"name": "b", "size": 3}]}
+                ]
+            }),
+            "Node",
+        );
+
+        let children = field(&obj, "children");
+        assert_eq!(
+            children.field_type,
+            SchemaType::Array(Box::new(SchemaType::Ref("Node".to_string())))
+        );
+        assert!(children.optional);
+    }
+
+    #[test]
+    fn test_nullable_parent_link() {
+        let obj = fold(
+            json!({
+                "id": 1,
+                "name": "Books",
+                "parent": {"id": 2, "name": "Media", "parent": null}
+            }),
+            "Category",
+        );
+
+        let parent = field(&obj, "parent");
+        assert_eq!(parent.field_type, SchemaType::Ref("Category".to_string()));
+        assert!(parent.nullable);
+    }
+
+    #[test]
+    fn test_similar_child_without_recursive_field_is_not_folded() {
+        let obj = fold(
+            json!({
+                "id": 1,
+                "name": "Ada",
+                "manager": {"id": 2, "name": "Grace"}
+            }),
+            "User",
+        );
+
+        assert!(matches!(
+            field(&obj, "manager").field_type,
+            SchemaType::Object(_)
+        ));
+    }
+
+    #[test]
+    fn test_nested_ancestor_uses_path_name() {
+        let obj = fold(
+            json!({
+                "title": "Docs",
+                "tree": {
+                    "label": "root",
+                    "depth": 0,
+                    "nodes": [{"label": "leaf", "depth": 1, "nodes": []}]
+                }
+            }),
+            "Site",
+        );
+
+        let tree = match &field(&obj, "tree").field_type {
+            SchemaType::Object(tree) => tree,
+            other => panic!("expected object, got {:?}", other),
+        };
+        assert_eq!(
+            field(tree, "nodes").field_type,
+            SchemaType::Array(Box::new(SchemaType::Ref("SiteTree".to_string())))
+        );
+    }
+}
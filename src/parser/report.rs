@@ -0,0 +1,203 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+
+use crate::types::{ObjectType, Schema, SchemaType};
+
+use super::stats::{path_key, SchemaStats, ARRAY_ITEM_SEGMENT};
+
+/// Per-path figures for an inferred schema, used to judge whether a set of
+/// samples is representative before trusting the generated types
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaReport {
+    pub samples: usize,
+    pub paths: Vec<PathReport>,
+}
+
+/// What was observed at one JSON path, e.g. `users[].address.city`
+#[derive(Debug, Clone, Serialize)]
+pub struct PathReport {
+    pub path: String,
+    /// Share of the enclosing objects that have this key; `None` for array elements
+    pub presence: Option<f64>,
+    pub null_ratio: f64,
+    /// Occurrences of each JSON kind (`string`, `integer`, `null`, ...)
+    pub types: BTreeMap<String, usize>,
+    /// Distinct scalar values, `None` when there were too many to count
+    pub distinct: Option<usize>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+    /// The first few distinct values, rendered as JSON
+    pub examples: Vec<String>,
+}
+
+impl SchemaReport {
+    /// Walk every field and array element of `schema`, looking up what
+    /// `stats` recorded at its path
+    pub fn new(schema: &Schema, stats: &SchemaStats) -> Self {
+        let mut report = Self {
+            samples: stats.get(&[]).map_or(0, |s| s.occurrences),
+            paths: Vec::new(),
+        };
+        let mut seen = HashSet::new();
+        report.visit(&schema.root_type, &mut Vec::new(), stats, &mut seen);
+        report
+    }
+
+    fn visit(
+        &mut self,
+        schema_type: &SchemaType,
+        path: &mut Vec<String>,
+        stats: &SchemaStats,
+        seen: &mut HashSet<String>,
+    ) {
+        match schema_type {
+            SchemaType::Object(obj) => self.visit_fields(obj, path, stats, seen),
+            SchemaType::DiscriminatedUnion { variants, .. } => {
+                for variant in variants {
+                    self.visit_fields(variant, path, stats, seen);
+                }
+            }
+            SchemaType::Array(inner) => {
+                path.push(ARRAY_ITEM_SEGMENT.to_string());
+                self.add(path, stats, seen);
+                self.visit(inner, path, stats, seen);
+                path.pop();
+            }
+            SchemaType::Tuple(types) => {
+                path.push(ARRAY_ITEM_SEGMENT.to_string());
+                self.add(path, stats, seen);
+                for t in types {
+                    self.visit(t, path, stats, seen);
+                }
+                path.pop();
+            }
+            SchemaType::Optional(inner) => self.visit(inner, path, stats, seen),
+            SchemaType::Union(types) => {
+                for t in types {
+                    self.visit(t, path, stats, seen);
+                }
+            }
+            // Map keys are data, and refs point back at a type already walked
+            _ => {}
+        }
+    }
+
+    fn visit_fields(
+        &mut self,
+        obj: &ObjectType,
+        path: &mut Vec<String>,
+        stats: &SchemaStats,
+        seen: &mut HashSet<String>,
+    ) {
+        for field in &obj.fields {
+            path.push(field.name.clone());
+            self.add(path, stats, seen);
+            self.visit(&field.field_type, path, stats, seen);
+            path.pop();
+        }
+    }
+
+    fn add(&mut self, path: &[String], stats: &SchemaStats, seen: &mut HashSet<String>) {
+        let key = path_key(path);
+        let Some(path_stats) = stats.get(path) else {
+            return;
+        };
+        if !seen.insert(key.clone()) {
+            return;
+        }
+
+        let presence = match path.split_last() {
+            Some((last, _)) if last == ARRAY_ITEM_SEGMENT => None,
+            Some((_, parent)) => stats
+                .get(parent)
+                .filter(|p| p.object_count > 0)
+                .map(|p| path_stats.occurrences as f64 / p.object_count as f64),
+            None => None,
+        };
+        self.paths.push(PathReport {
+            path: key,
+            presence,
+            null_ratio: path_stats.null_count as f64 / path_stats.occurrences as f64,
+            types: path_stats
+                .kinds
+                .iter()
+                .map(|(kind, count)| (kind.to_string(), *count))
+                .collect(),
+            distinct: path_stats.distinct_scalars(),
+            min: path_stats.number_range.map(|(min, _)| min),
+            max: path_stats.number_range.map(|(_, max)| max),
+            min_length: path_stats.string_length.map(|(min, _)| min),
+            max_length: path_stats.string_length.map(|(_, max)| max),
+            examples: path_stats.examples.clone(),
+        });
+    }
+
+    /// The figures for one path, e.g. `users[].name`
+    pub fn get(&self, path: &str) -> Option<&PathReport> {
+        self.paths.iter().find(|p| p.path == path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{InferenceConfig, SchemaBuilder};
+    use serde_json::json;
+
+    fn report(samples: &[serde_json::Value]) -> SchemaReport {
+        let mut builder = SchemaBuilder::new("Test", InferenceConfig::default());
+        for sample in samples {
+            builder.add_sample(sample);
+        }
+        let (schema, stats) = builder.finish_with_stats().unwrap();
+        SchemaReport::new(&schema, &stats)
+    }
+
+    #[test]
+    fn test_presence_and_null_ratio() {
+        let report = report(&[
+            json!({"id": 1, "bio": null}),
+            json!({"id": 2, "bio": "hi"}),
+            json!({"id": 3}),
+            json!({"id": 4, "bio": "hi"}),
+        ]);
+        assert_eq!(report.samples, 4);
+
+        let bio = report.get("bio").unwrap();
+        assert_eq!(bio.presence, Some(0.75));
+        assert!((bio.null_ratio - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(bio.types["string"], 2);
+        assert_eq!(bio.types["null"], 1);
+        assert_eq!(bio.distinct, Some(1));
+
+        let id = report.get("id").unwrap();
+        assert_eq!(id.presence, Some(1.0));
+        assert_eq!((id.min, id.max), (Some(1.0), Some(4.0)));
+        assert_eq!(id.examples, vec!["1", "2", "3"]);
+    }
+
+    #[test]
+    fn test_nested_and_array_paths() {
+        let report = report(&[json!({
+            "users": [{"name": "Ada", "address": {"city": "London"}}, {"name": "Bob"}]
+        })]);
+        let paths: Vec<&str> = report.paths.iter().map(|p| p.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "users",
+                "users[]",
+                "users[].address",
+                "users[].address.city",
+                "users[].name"
+            ]
+        );
+        assert_eq!(report.get("users[]").unwrap().presence, None);
+        assert_eq!(report.get("users[].address").unwrap().presence, Some(0.5));
+
+        let name = report.get("users[].name").unwrap();
+        assert_eq!((name.min_length, name.max_length), (Some(3), Some(3)));
+    }
+}
@@ -0,0 +1,420 @@
+use crate::types::{ObjectType, SchemaType};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use super::inference::{merge_objects, unify_types};
+
+/// Distinct string values tracked per path before giving up on counting them
+const MAX_TRACKED_VALUES: usize = 256;
+
+/// Example values kept per path for reports
+const MAX_EXAMPLES: usize = 3;
+
+/// Longest array whose element types are tracked position by position
+pub const MAX_TUPLE_LEN: usize = 4;
+
+/// Observations collected for a single JSON path across every sample
+#[derive(Debug, Clone, Default)]
+pub struct PathStats {
+    pub occurrences: usize,
+    pub null_count: usize,
+    pub object_count: usize,
+    /// How often each JSON kind (`string`, `integer`, `object`, ...) was seen
+    pub kinds: BTreeMap<&'static str, usize>,
+    /// Distinct scalar values rendered as JSON, up to the tracking limit
+    pub scalar_values: HashSet<String>,
+    pub scalar_values_overflow: bool,
+    /// The first few distinct scalar values, in the order they were seen
+    pub examples: Vec<String>,
+    pub string_count: usize,
+    pub string_values: HashMap<String, usize>,
+    pub string_values_overflow: bool,
+    /// Smallest and largest whole number seen here, clamped to `i128`
+    pub int_range: Option<(i128, i128)>,
+    /// Smallest and largest integer written as a string (`"42"`) seen here
+    pub numeric_string_range: Option<(i128, i128)>,
+    /// Smallest and largest number of any kind seen here
+    pub number_range: Option<(f64, f64)>,
+    /// Shortest and longest string seen here, in characters
+    pub string_length: Option<(usize, usize)>,
+    /// Fewest and most elements of the arrays seen here
+    pub array_length: Option<(usize, usize)>,
+    pub array_count: usize,
+    /// Element types by position, while every array seen here had the same
+    /// length (at most [`MAX_TUPLE_LEN`])
+    pub positions: Option<Vec<SchemaType>>,
+    /// Objects seen here with a string in a tag field (`type`, `kind`, ...),
+    /// by tag field
+    pub tagged: BTreeMap<&'static str, TaggedObjects>,
+}
+
+/// The objects at one path that held a string in the same tag field, merged
+/// per tag value in the order the values were first seen
+#[derive(Debug, Clone, Default)]
+pub struct TaggedObjects {
+    pub count: usize,
+    pub variants: Vec<(String, ObjectType)>,
+    /// Set once there were too many tag values to track
+    pub overflow: bool,
+}
+
+impl PathStats {
+    pub fn record(&mut self, value: &Value) {
+        self.occurrences += 1;
+        *self.kinds.entry(json_kind(value)).or_default() += 1;
+        match value {
+            Value::Null => self.null_count += 1,
+            Value::Object(_) => self.object_count += 1,
+            Value::Array(_) => {}
+            scalar => self.record_scalar(scalar.to_string()),
+        }
+        if let Some(n) = value.as_number().and_then(whole_number) {
+            self.int_range = Some(widen(self.int_range, n));
+        }
+        if let Some(n) = value.as_f64() {
+            self.number_range = Some(widen(self.number_range, n));
+        }
+        if let Value::String(s) = value {
+            self.string_count += 1;
+            self.string_length = Some(widen(self.string_length, s.chars().count()));
+            if let Some(n) = s.parse::<i128>().ok().filter(|n| n.to_string() == *s) {
+                self.numeric_string_range = Some(widen(self.numeric_string_range, n));
+            }
+            if let Some(count) = self.string_values.get_mut(s) {
+                *count += 1;
+            } else if self.string_values.len() < MAX_TRACKED_VALUES {
+                self.string_values.insert(s.clone(), 1);
+            } else {
+                self.string_values_overflow = true;
+            }
+        }
+    }
+
+    /// Record an array too long to be a tuple, by length only
+    pub fn record_array_len(&mut self, len: usize) {
+        self.array_count += 1;
+        self.array_length = Some(widen(self.array_length, len));
+        self.positions = None;
+    }
+
+    pub fn record_array(&mut self, element_types: &[SchemaType]) {
+        self.array_count += 1;
+        self.array_length = Some(widen(self.array_length, element_types.len()));
+        self.positions = match self.positions.take() {
+            None if self.array_count == 1 && element_types.len() <= MAX_TUPLE_LEN => {
+                Some(element_types.to_vec())
+            }
+            Some(current) if current.len() == element_types.len() => Some(
+                current
+                    .into_iter()
+                    .zip(element_types)
+                    .map(|(a, b)| unify_types(&[a, b.clone()]))
+                    .collect(),
+            ),
+            _ => None,
+        };
+    }
+
+    /// Record an object whose `tag` field held `value`
+    pub fn record_tagged(&mut self, tag: &'static str, value: &str, obj: &ObjectType) {
+        let tagged = self.tagged.entry(tag).or_default();
+        tagged.count += 1;
+        if let Some((_, variant)) = tagged.variants.iter_mut().find(|(v, _)| v == value) {
+            *variant = merge_objects(&[variant, obj]);
+        } else if tagged.variants.len() < MAX_TRACKED_VALUES {
+            tagged.variants.push((value.to_string(), obj.clone()));
+        } else {
+            tagged.overflow = true;
+        }
+    }
+
+    fn record_scalar(&mut self, rendered: String) {
+        if self.scalar_values.contains(&rendered) {
+            return;
+        }
+        if self.scalar_values.len() >= MAX_TRACKED_VALUES {
+            self.scalar_values_overflow = true;
+            return;
+        }
+        if self.examples.len() < MAX_EXAMPLES {
+            self.examples.push(rendered.clone());
+        }
+        self.scalar_values.insert(rendered);
+    }
+
+    /// Number of distinct string values, or `None` if there were too many to track
+    pub fn distinct_strings(&self) -> Option<usize> {
+        if self.string_values_overflow {
+            None
+        } else {
+            Some(self.string_values.len())
+        }
+    }
+
+    /// Whether some value may have been seen here more than once: a tracked
+    /// string counted twice, more integers than the observed range can hold,
+    /// or too many strings to tell
+    pub fn has_repeated_value(&self) -> bool {
+        let integers = self.kinds.get("integer").copied().unwrap_or(0) as i128;
+        self.string_values_overflow
+            || self.string_values.values().any(|&count| count > 1)
+            || self
+                .int_range
+                .is_some_and(|(min, max)| max.saturating_sub(min) < integers - 1)
+    }
+
+    /// Number of distinct scalar values of any kind, or `None` if there were
+    /// too many to track
+    pub fn distinct_scalars(&self) -> Option<usize> {
+        if self.scalar_values_overflow {
+            None
+        } else {
+            Some(self.scalar_values.len())
+        }
+    }
+}
+
+/// Name of the JSON kind of `value`, telling whole numbers from other numbers
+fn json_kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if whole_number(n).is_some() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn widen<T: PartialOrd + Copy>(range: Option<(T, T)>, value: T) -> (T, T) {
+    match range {
+        Some((min, max)) => (
+            if value < min { value } else { min },
+            if value > max { value } else { max },
+        ),
+        None => (value, value),
+    }
+}
+
+/// The value of a whole JSON number, clamped to `i128` so integers too large
+/// for 64 bits still count as large
+pub fn whole_number(n: &serde_json::Number) -> Option<i128> {
+    if let Some(i) = n.as_i64() {
+        return Some(i.into());
+    }
+    if let Some(u) = n.as_u64() {
+        return Some(u.into());
+    }
+    // Only reachable with arbitrary precision: the literal is kept as written
+    let literal = n.to_string();
+    let digits = literal.strip_prefix('-').unwrap_or(&literal);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some(literal.parse().unwrap_or(if literal.starts_with('-') {
+        i128::MIN
+    } else {
+        i128::MAX
+    }))
+}
+
+/// Per-path observations gathered during inference, keyed by [`path_key`]
+#[derive(Debug, Clone, Default)]
+pub struct SchemaStats {
+    paths: HashMap<String, PathStats>,
+}
+
+impl SchemaStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, path: &[String], value: &Value) {
+        self.paths.entry(path_key(path)).or_default().record(value);
+    }
+
+    pub fn record_array(&mut self, path: &[String], element_types: &[SchemaType]) {
+        self.paths
+            .entry(path_key(path))
+            .or_default()
+            .record_array(element_types);
+    }
+
+    pub fn record_array_len(&mut self, path: &[String], len: usize) {
+        self.paths
+            .entry(path_key(path))
+            .or_default()
+            .record_array_len(len);
+    }
+
+    pub fn record_tagged(
+        &mut self,
+        path: &[String],
+        tag: &'static str,
+        value: &str,
+        obj: &ObjectType,
+    ) {
+        self.paths
+            .entry(path_key(path))
+            .or_default()
+            .record_tagged(tag, value, obj);
+    }
+
+    pub fn get(&self, path: &[String]) -> Option<&PathStats> {
+        self.paths.get(&path_key(path))
+    }
+}
+
+/// Segment used in inference paths for the elements of an array
+pub const ARRAY_ITEM_SEGMENT: &str = "[]";
+
+/// Segment standing for every value of a map. Samples record map values
+/// under their own keys, so no stats are kept for this segment.
+pub const MAP_VALUE_SEGMENT: &str = "*";
+
+/// Render an inference path as a JSON-path-like key (e.g. `users[].address.city`)
+pub fn path_key(path: &[String]) -> String {
+    let mut key = String::new();
+    for segment in path {
+        if segment != ARRAY_ITEM_SEGMENT && !key.is_empty() {
+            key.push('.');
+        }
+        key.push_str(segment);
+    }
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn path(segments: &[&str]) -> Vec<String> {
+        segments.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_path_key() {
+        assert_eq!(path_key(&[]), "");
+        assert_eq!(path_key(&path(&["status"])), "status");
+        assert_eq!(path_key(&path(&["users", "[]", "address", "city"])), "users[].address.city");
+        assert_eq!(path_key(&path(&["[]", "id"])), "[].id");
+    }
+
+    #[test]
+    fn test_record_counts_string_values() {
+        let mut stats = SchemaStats::new();
+        let p = path(&["status"]);
+        stats.record(&p, &json!("active"));
+        stats.record(&p, &json!("active"));
+        stats.record(&p, &json!("pending"));
+        stats.record(&p, &json!(3));
+
+        let s = stats.get(&p).unwrap();
+        assert_eq!(s.occurrences, 4);
+        assert_eq!(s.string_count, 3);
+        assert_eq!(s.distinct_strings(), Some(2));
+        assert_eq!(s.string_values["active"], 2);
+    }
+
+    #[test]
+    fn test_record_counts_kinds_and_examples() {
+        let mut stats = PathStats::default();
+        for value in [json!(1), json!(1), json!(2.5), json!(null), json!("a"), json!(true)] {
+            stats.record(&value);
+        }
+        assert_eq!(stats.kinds["integer"], 2);
+        assert_eq!(stats.kinds["number"], 1);
+        assert_eq!(stats.null_count, 1);
+        assert_eq!(stats.distinct_scalars(), Some(4));
+        assert_eq!(stats.examples, vec!["1", "2.5", "\"a\""]);
+    }
+
+    #[test]
+    fn test_record_overflows_distinct_values() {
+        let mut stats = PathStats::default();
+        for i in 0..=MAX_TRACKED_VALUES {
+            stats.record(&json!(format!("value-{}", i)));
+        }
+        assert_eq!(stats.distinct_strings(), None);
+    }
+
+    #[test]
+    fn test_record_array_positions() {
+        let mut stats = SchemaStats::new();
+        let at = path(&["at"]);
+        stats.record_array(&at, &[SchemaType::Integer, SchemaType::String]);
+        stats.record_array(&at, &[SchemaType::Number, SchemaType::String]);
+
+        let s = stats.get(&at).unwrap();
+        assert_eq!(s.array_count, 2);
+        assert_eq!(
+            s.positions,
+            Some(vec![SchemaType::Number, SchemaType::String])
+        );
+
+        stats.record_array(&at, &[SchemaType::Integer]);
+        assert_eq!(stats.get(&at).unwrap().positions, None);
+    }
+
+    #[test]
+    fn test_record_tracks_integer_range() {
+        let mut stats = PathStats::default();
+        stats.record(&json!(-3));
+        stats.record(&json!(2.5));
+        stats.record(&json!(u64::MAX));
+        assert_eq!(stats.int_range, Some((-3, u64::MAX.into())));
+    }
+
+    #[test]
+    fn test_has_repeated_value() {
+        let mut stats = PathStats::default();
+        for value in [json!("a"), json!("b"), json!(1), json!(3)] {
+            stats.record(&value);
+        }
+        assert!(!stats.has_repeated_value());
+
+        stats.record(&json!(2));
+        stats.record(&json!(1));
+        assert!(stats.has_repeated_value());
+
+        let mut stats = PathStats::default();
+        stats.record(&json!("a@b.co"));
+        stats.record(&json!("a@b.co"));
+        assert!(stats.has_repeated_value());
+
+        let mut stats = PathStats::default();
+        for i in 0..=MAX_TRACKED_VALUES {
+            stats.record(&json!(format!("user{}@b.co", i)));
+        }
+        assert!(stats.string_values_overflow);
+        assert!(stats.has_repeated_value());
+    }
+
+    #[test]
+    fn test_record_tracks_numeric_string_range() {
+        let mut stats = PathStats::default();
+        stats.record(&json!("4155550123"));
+        stats.record(&json!("-7"));
+        stats.record(&json!("007"));
+        stats.record(&json!(12));
+        assert_eq!(stats.numeric_string_range, Some((-7, 4155550123)));
+        assert_eq!(stats.int_range, Some((12, 12)));
+    }
+
+    #[test]
+    fn test_whole_number_beyond_64_bits() {
+        let big: Value = serde_json::from_str("123456789012345678901234567890").unwrap();
+        assert_eq!(
+            whole_number(big.as_number().unwrap()),
+            Some(123456789012345678901234567890)
+        );
+
+        let huge: Value = serde_json::from_str(&format!("-{}", "9".repeat(50))).unwrap();
+        assert_eq!(whole_number(huge.as_number().unwrap()), Some(i128::MIN));
+
+        let float: Value = serde_json::from_str("1.0").unwrap();
+        assert_eq!(whole_number(float.as_number().unwrap()), None);
+    }
+}
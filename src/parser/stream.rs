@@ -0,0 +1,433 @@
+use crate::types::{Schema, SchemaType};
+use anyhow::Result;
+use serde::de::{self, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde_json::Value;
+use std::fmt;
+use std::io::BufRead;
+
+use super::builder::{ArraySample, SchemaBuilder};
+use super::inference::{infer_type, ArrayInference, ObjectInference, TAG_FIELDS};
+use super::patterns::StringPatterns;
+use super::stats::{SchemaStats, ARRAY_ITEM_SEGMENT};
+use super::InferenceConfig;
+
+/// Key serde_json's `arbitrary_precision` feature wraps numbers in, so they
+/// reach a visitor as a one-entry map holding the number's text
+const NUMBER_TOKEN: &str = "$serde_json::private::Number";
+
+/// Which elements of a top-level array to infer from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Sampling {
+    #[default]
+    All,
+    /// The first `n` elements
+    First(usize),
+    /// `n` elements picked uniformly at random (reservoir sampling). The
+    /// seed is fixed, so the same input always gives the same schema.
+    Reservoir(usize),
+}
+
+/// How much of a top-level array was read and how much of it was inferred
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StreamSummary {
+    pub elements: usize,
+    pub sampled: usize,
+}
+
+/// Feed one JSON document from `reader` into `builder` without building the
+/// whole `Value` tree. A top-level array is read one element at a time, and
+/// `sampling` decides which elements are inferred; any other document is
+/// inferred as a single sample, straight from its tokens. Returns `None`
+/// when the root is not an array.
+pub fn read_json_stream<R: BufRead>(
+    mut reader: R,
+    builder: &mut SchemaBuilder,
+    sampling: Sampling,
+) -> Result<Option<StreamSummary>> {
+    let array_root = first_byte(&mut reader)? == Some(b'[');
+    let mut de = serde_json::Deserializer::from_reader(reader);
+    if !array_root {
+        builder.add_sample_from(&mut de)?;
+        de.end()?;
+        return Ok(None);
+    }
+
+    let summary = de.deserialize_seq(ElementVisitor {
+        sample: builder.begin_array(),
+        sampling,
+    })?;
+    de.end()?;
+    Ok(Some(summary))
+}
+
+pub fn parse_json_reader<R: BufRead>(
+    reader: R,
+    name: &str,
+    config: &InferenceConfig,
+    sampling: Sampling,
+) -> Result<Schema> {
+    let mut builder = SchemaBuilder::new(name, config.clone());
+    read_json_stream(reader, &mut builder, sampling)?;
+    builder.finish()
+}
+
+/// Skip leading whitespace and peek at the first byte of the document
+fn first_byte<R: BufRead>(reader: &mut R) -> Result<Option<u8>> {
+    loop {
+        let buf = reader.fill_buf()?;
+        let Some(&byte) = buf.first() else {
+            return Ok(None);
+        };
+        if byte.is_ascii_whitespace() {
+            reader.consume(1);
+        } else {
+            return Ok(Some(byte));
+        }
+    }
+}
+
+struct ElementVisitor<'a> {
+    sample: ArraySample<'a>,
+    sampling: Sampling,
+}
+
+impl<'de> Visitor<'de> for ElementVisitor<'_> {
+    type Value = StreamSummary;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a JSON array")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(mut self, mut seq: A) -> Result<StreamSummary, A::Error> {
+        let mut elements = 0;
+        match self.sampling {
+            Sampling::All => {
+                while self.sample.add_element_from(&mut seq)? {
+                    elements += 1;
+                }
+            }
+            Sampling::First(n) => {
+                while elements < n && self.sample.add_element_from(&mut seq)? {
+                    elements += 1;
+                }
+                // The rest still has to be valid JSON, but is never built
+                while seq.next_element::<IgnoredAny>()?.is_some() {
+                    elements += 1;
+                }
+            }
+            Sampling::Reservoir(n) => {
+                let mut rng = XorShift(0x9E37_79B9_7F4A_7C15);
+                let mut reservoir: Vec<(usize, Value)> = Vec::with_capacity(n);
+                while let Some(value) = seq.next_element::<Value>()? {
+                    if reservoir.len() < n {
+                        reservoir.push((elements, value));
+                    } else {
+                        let slot = (rng.next() % (elements as u64 + 1)) as usize;
+                        if slot < n {
+                            reservoir[slot] = (elements, value);
+                        }
+                    }
+                    elements += 1;
+                }
+                // Infer in input order so field order matches the file
+                reservoir.sort_by_key(|(index, _)| *index);
+                for (_, value) in &reservoir {
+                    self.sample.add_element(value);
+                }
+            }
+        }
+
+        let summary = StreamSummary {
+            elements,
+            sampled: self.sample.len(),
+        };
+        self.sample.finish();
+        Ok(summary)
+    }
+}
+
+/// Infers the type of one JSON value straight from a deserializer's tokens,
+/// like [`infer_type`] but without building the `Value`: arrays and objects
+/// are folded in one element or entry at a time.
+pub struct InferSeed<'a> {
+    pub path: &'a mut Vec<String>,
+    pub stats: &'a mut SchemaStats,
+    pub patterns: &'a StringPatterns,
+}
+
+impl InferSeed<'_> {
+    fn child(&mut self) -> InferSeed<'_> {
+        InferSeed {
+            path: self.path,
+            stats: self.stats,
+            patterns: self.patterns,
+        }
+    }
+
+    fn scalar(self, value: Value) -> SchemaType {
+        infer_type(&value, self.path, self.stats, self.patterns)
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for InferSeed<'_> {
+    type Value = SchemaType;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<SchemaType, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for InferSeed<'_> {
+    type Value = SchemaType;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a JSON value")
+    }
+
+    fn visit_unit<E>(self) -> Result<SchemaType, E> {
+        Ok(self.scalar(Value::Null))
+    }
+
+    fn visit_bool<E>(self, b: bool) -> Result<SchemaType, E> {
+        Ok(self.scalar(Value::Bool(b)))
+    }
+
+    fn visit_i64<E>(self, n: i64) -> Result<SchemaType, E> {
+        Ok(self.scalar(Value::from(n)))
+    }
+
+    fn visit_u64<E>(self, n: u64) -> Result<SchemaType, E> {
+        Ok(self.scalar(Value::from(n)))
+    }
+
+    fn visit_f64<E>(self, n: f64) -> Result<SchemaType, E> {
+        Ok(self.scalar(Value::from(n)))
+    }
+
+    fn visit_str<E>(self, s: &str) -> Result<SchemaType, E> {
+        Ok(self.scalar(Value::String(s.to_string())))
+    }
+
+    fn visit_string<E>(self, s: String) -> Result<SchemaType, E> {
+        Ok(self.scalar(Value::String(s)))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(mut self, mut seq: A) -> Result<SchemaType, A::Error> {
+        self.stats.record(self.path, &Value::Array(Vec::new()));
+        let mut array = ArrayInference::default();
+        self.path.push(ARRAY_ITEM_SEGMENT.to_string());
+        while let Some(element_type) = seq.next_element_seed(self.child())? {
+            array.add(element_type);
+        }
+        self.path.pop();
+        Ok(array.finish(self.path, self.stats))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(mut self, mut map: A) -> Result<SchemaType, A::Error> {
+        let mut key = map.next_key::<String>()?;
+        if key.as_deref() == Some(NUMBER_TOKEN) {
+            let number: String = map.next_value()?;
+            let value = serde_json::from_str(&number).map_err(de::Error::custom)?;
+            return Ok(self.scalar(value));
+        }
+
+        self.stats
+            .record(self.path, &Value::Object(Default::default()));
+        let mut object = ObjectInference::new(self.path);
+        while let Some(name) = key {
+            self.path.push(object.segment(&name));
+            // Tags are small, and their value is needed to tell variants apart
+            let (field_type, value) = if TAG_FIELDS.contains(&name.as_str()) {
+                let value: Value = map.next_value()?;
+                let field_type = infer_type(&value, self.path, self.stats, self.patterns);
+                (field_type, value.as_str().map(str::to_string))
+            } else {
+                (map.next_value_seed(self.child())?, None)
+            };
+            self.path.pop();
+            object.add(&name, field_type, value.as_deref());
+            key = map.next_key()?;
+        }
+        Ok(object.finish(self.path, self.stats))
+    }
+}
+
+/// Small deterministic PRNG for reservoir sampling
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_json_string_with_config;
+    use crate::types::SchemaType;
+
+    const USERS: &str = r#"[
+        {"id": 1, "name": "Ada", "tags": ["a", "b"], "at": [1.5, 2.5]},
+        {"id": 2, "name": "Bob", "email": "bob@example.com", "tags": [], "at": [3, 4.5]},
+        {"id": 3, "name": null, "type": "admin", "at": [0.5, 1]}
+    ]"#;
+
+    const EVENTS: &str = r#"[
+        {"type": "click", "x": 1, "y": 2},
+        {"type": "view", "url": "https://example.com"},
+        {"type": "click", "x": 3.5, "y": 4},
+        {"type": "view", "url": "https://example.com/a", "ref": null}
+    ]"#;
+
+    #[test]
+    fn test_stream_matches_in_memory() {
+        let config = InferenceConfig::default();
+        for json in [USERS, EVENTS] {
+            let streamed =
+                parse_json_reader(json.as_bytes(), "Rows", &config, Sampling::All).unwrap();
+            let in_memory = parse_json_string_with_config(json, "Rows", &config).unwrap();
+            assert_eq!(format!("{:?}", streamed), format!("{:?}", in_memory));
+        }
+    }
+
+    #[test]
+    fn test_stream_matches_in_memory_across_batches() {
+        let config = InferenceConfig::default();
+        let array = |first: &str, rest: &str, last: &str| {
+            let mut elements = vec![first.to_string()];
+            elements.extend(std::iter::repeat_n(rest.to_string(), 300));
+            elements.push(last.to_string());
+            format!("[{}]", elements.join(","))
+        };
+        for json in [
+            array(r#"{"v": "a@b.co"}"#, r#"{"v": "x"}"#, r#"{"v": 1}"#),
+            array(r#"{"v": "42"}"#, r#"{"v": "1.5"}"#, r#"{"v": 7}"#),
+            array(
+                r#"{"type": "click", "x": 1}"#,
+                r#"{"type": "view", "x": 2}"#,
+                r#"{"type": "view", "page": "/"}"#,
+            ),
+        ] {
+            let streamed =
+                parse_json_reader(json.as_bytes(), "Rows", &config, Sampling::All).unwrap();
+            let in_memory = parse_json_string_with_config(&json, "Rows", &config).unwrap();
+            assert_eq!(format!("{:?}", streamed), format!("{:?}", in_memory));
+        }
+    }
+
+    #[test]
+    fn test_stream_non_array_root() {
+        let json = r#"  {"id": 1, "items": [1, 2]}"#;
+        let config = InferenceConfig::default();
+        let streamed = parse_json_reader(json.as_bytes(), "Order", &config, Sampling::All).unwrap();
+        let in_memory = parse_json_string_with_config(json, "Order", &config).unwrap();
+        assert_eq!(format!("{:?}", streamed), format!("{:?}", in_memory));
+    }
+
+    #[test]
+    fn test_stream_object_root_matches_in_memory() {
+        let config = InferenceConfig::default();
+        let users: Vec<String> = (0..300)
+            .map(|i| {
+                format!(
+                    r#""{}": {{"name": "user{}", "score": {}.5}}"#,
+                    1000 + i,
+                    i,
+                    i
+                )
+            })
+            .collect();
+        for json in [
+            format!(r#"{{"data": {}, "meta": {{"page": 1}}}}"#, USERS),
+            format!(r#"{{"events": {}, "total": 12345678901234567890}}"#, EVENTS),
+            format!(r#"{{"users": {{{}}}}}"#, users.join(",")),
+        ] {
+            let streamed =
+                parse_json_reader(json.as_bytes(), "Export", &config, Sampling::All).unwrap();
+            let in_memory = parse_json_string_with_config(&json, "Export", &config).unwrap();
+            assert_eq!(format!("{:?}", streamed), format!("{:?}", in_memory));
+        }
+    }
+
+    #[test]
+    fn test_sample_first_elements() {
+        let mut builder = SchemaBuilder::new("Users", InferenceConfig::default());
+        let summary = read_json_stream(USERS.as_bytes(), &mut builder, Sampling::First(2))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            summary,
+            StreamSummary {
+                elements: 3,
+                sampled: 2
+            }
+        );
+
+        let schema = builder.finish().unwrap();
+        let obj = schema.root_object().unwrap();
+        assert!(obj.fields.iter().all(|f| f.name != "type"));
+    }
+
+    #[test]
+    fn test_reservoir_is_reproducible() {
+        let json = format!(
+            "[{}]",
+            (0..500)
+                .map(|i| format!(r#"{{"n": {}}}"#, i))
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+        let run = || {
+            let mut builder = SchemaBuilder::new("Rows", InferenceConfig::default());
+            let summary = read_json_stream(json.as_bytes(), &mut builder, Sampling::Reservoir(10))
+                .unwrap()
+                .unwrap();
+            (summary, builder.finish_with_stats().unwrap().1)
+        };
+        let (summary, stats) = run();
+        assert_eq!(
+            summary,
+            StreamSummary {
+                elements: 500,
+                sampled: 10
+            }
+        );
+
+        let n = |stats: &crate::parser::SchemaStats| {
+            stats
+                .get(&["[]".to_string(), "n".to_string()])
+                .unwrap()
+                .examples
+                .clone()
+        };
+        assert_eq!(n(&stats), n(&run().1));
+    }
+
+    #[test]
+    fn test_stream_rejects_trailing_content() {
+        let mut builder = SchemaBuilder::new("Rows", InferenceConfig::default());
+        assert!(read_json_stream("[1] [2]".as_bytes(), &mut builder, Sampling::All).is_err());
+        assert!(read_json_stream("[1, ".as_bytes(), &mut builder, Sampling::All).is_err());
+    }
+
+    #[test]
+    fn test_stream_empty_array() {
+        let config = InferenceConfig {
+            flat_mode: true,
+            ..Default::default()
+        };
+        let schema = parse_json_reader("[]".as_bytes(), "Rows", &config, Sampling::All);
+        let in_memory = parse_json_string_with_config("[]", "Rows", &config);
+        assert_eq!(schema.is_err(), in_memory.is_err());
+        if let Ok(schema) = schema {
+            assert_eq!(
+                schema.root_type,
+                SchemaType::Array(Box::new(SchemaType::Any))
+            );
+        }
+    }
+}
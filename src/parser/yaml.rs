@@ -0,0 +1,209 @@
+use crate::types::{MoldError, Schema};
+use anyhow::Result;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use serde_yaml_ng::Value as YamlValue;
+
+use super::builder::SchemaBuilder;
+use super::InferenceConfig;
+
+lazy_static! {
+    /// YAML 1.1 timestamps, which may use a space instead of `T`, single-digit
+    /// fields and an hour-only offset (`2001-12-14 21:59:43.10 -5`)
+    static ref YAML_TIMESTAMP_RE: Regex = Regex::new(
+        r"^(\d{4})-(\d{1,2})-(\d{1,2})(?:[Tt]|[ \t]+)(\d{1,2}):(\d{2}):(\d{2})(\.\d*)?(?:[ \t]*(?:(Z)|([-+])(\d{1,2})(?::(\d{2}))?))?$"
+    ).unwrap();
+}
+
+/// Every document in a YAML stream, as JSON. Merge keys (`<<`) are resolved,
+/// non-string keys become strings and timestamps are rewritten as RFC 3339
+/// so they're detected like JSON date-times. Empty documents are skipped.
+pub fn parse_yaml_documents(input: &str) -> Result<Vec<Value>, MoldError> {
+    let mut documents = Vec::new();
+    for document in serde_yaml_ng::Deserializer::from_str(input) {
+        let mut value = YamlValue::deserialize(document)?;
+        value.apply_merge()?;
+        if !value.is_null() {
+            documents.push(to_json(value));
+        }
+    }
+    Ok(documents)
+}
+
+/// Infer one schema from a YAML stream, each document being a sample
+pub fn parse_yaml_string(input: &str, name: &str, config: &InferenceConfig) -> Result<Schema> {
+    let mut builder = SchemaBuilder::new(name, config.clone());
+    for document in parse_yaml_documents(input)? {
+        builder.add_sample(&document);
+    }
+    builder.finish()
+}
+
+fn to_json(value: YamlValue) -> Value {
+    match value {
+        YamlValue::Null => Value::Null,
+        YamlValue::Bool(b) => Value::Bool(b),
+        YamlValue::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Value::from(i)
+            } else if let Some(u) = n.as_u64() {
+                Value::from(u)
+            } else {
+                // `.inf` and `.nan` have no JSON equivalent
+                n.as_f64()
+                    .and_then(serde_json::Number::from_f64)
+                    .map_or(Value::Null, Value::Number)
+            }
+        }
+        YamlValue::String(s) => Value::String(normalize_timestamp(&s).unwrap_or(s)),
+        YamlValue::Sequence(items) => Value::Array(items.into_iter().map(to_json).collect()),
+        YamlValue::Mapping(mapping) => {
+            let mut object = Map::new();
+            for (key, value) in mapping {
+                object.insert(key_string(key), to_json(value));
+            }
+            Value::Object(object)
+        }
+        // Custom tags such as `!Ref` don't change the shape of the data
+        YamlValue::Tagged(tagged) => to_json(tagged.value),
+    }
+}
+
+fn key_string(key: YamlValue) -> String {
+    match to_json(key) {
+        Value::String(s) => s,
+        other => other.to_string(),
+    }
+}
+
+/// A YAML timestamp as RFC 3339, e.g. `2001-12-14T21:59:43.10-05:00`
+fn normalize_timestamp(s: &str) -> Option<String> {
+    let caps = YAML_TIMESTAMP_RE.captures(s)?;
+    let part = |i: usize| caps.get(i).map_or("", |m| m.as_str());
+    let fraction = match part(7) {
+        "." => "",
+        fraction => fraction,
+    };
+    let offset = match (part(8), part(9)) {
+        ("Z", _) => "Z".to_string(),
+        (_, "") => String::new(),
+        (_, sign) => {
+            let minutes = match part(11) {
+                "" => "00",
+                minutes => minutes,
+            };
+            format!("{}{:0>2}:{}", sign, part(10), minutes)
+        }
+    };
+    Some(format!(
+        "{}-{:0>2}-{:0>2}T{:0>2}:{}:{}{}{}",
+        part(1),
+        part(2),
+        part(3),
+        part(4),
+        part(5),
+        part(6),
+        fraction,
+        offset
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SchemaType;
+    use serde_json::json;
+
+    #[test]
+    fn test_yaml_converts_to_json() {
+        let input = "\
+id: 1
+name: Ada
+ratio: 0.5
+active: yes
+tags: [a, b]
+address:
+  city: London
+  zip: ~
+404: not found
+";
+        let documents = parse_yaml_documents(input).unwrap();
+        assert_eq!(
+            documents,
+            vec![json!({
+                "id": 1,
+                "name": "Ada",
+                "ratio": 0.5,
+                "active": "yes",
+                "tags": ["a", "b"],
+                "address": {"city": "London", "zip": null},
+                "404": "not found"
+            })]
+        );
+    }
+
+    #[test]
+    fn test_multiple_documents_are_samples() {
+        let input = "---\nid: 1\n---\nid: 2\nnote: hi\n---\n";
+        assert_eq!(parse_yaml_documents(input).unwrap().len(), 2);
+
+        let schema = parse_yaml_string(input, "Row", &InferenceConfig::default()).unwrap();
+        let obj = schema.root_object().unwrap();
+        assert!(!obj.fields[0].optional);
+        assert_eq!(obj.fields[1].name, "note");
+        assert!(obj.fields[1].optional);
+    }
+
+    #[test]
+    fn test_merge_keys_and_tags() {
+        let input = "\
+base: &base
+  retries: 3
+service:
+  <<: *base
+  name: !Ref api
+";
+        let documents = parse_yaml_documents(input).unwrap();
+        assert_eq!(
+            documents[0]["service"],
+            json!({"retries": 3, "name": "api"})
+        );
+    }
+
+    #[test]
+    fn test_timestamps_become_datetimes() {
+        assert_eq!(
+            normalize_timestamp("2001-12-14 21:59:43.10 -5").as_deref(),
+            Some("2001-12-14T21:59:43.10-05:00")
+        );
+        assert_eq!(
+            normalize_timestamp("2001-12-14t21:59:43Z").as_deref(),
+            Some("2001-12-14T21:59:43Z")
+        );
+        assert_eq!(
+            normalize_timestamp("2002-1-5 1:02:03").as_deref(),
+            Some("2002-01-05T01:02:03")
+        );
+        assert_eq!(normalize_timestamp("2002-12-14"), None);
+        assert_eq!(normalize_timestamp("not a date"), None);
+
+        let schema = parse_yaml_string(
+            "created: 2001-12-14 21:59:43.10 -5\nday: 2002-12-14\n",
+            "Event",
+            &InferenceConfig::default(),
+        )
+        .unwrap();
+        let obj = schema.root_object().unwrap();
+        assert_eq!(obj.fields[0].field_type, SchemaType::DateTime);
+        assert_eq!(obj.fields[1].field_type, SchemaType::Date);
+    }
+
+    #[test]
+    fn test_invalid_yaml_reports_location() {
+        let err = parse_yaml_documents("a: [1, 2\nb: 3\n").unwrap_err();
+        assert!(err.to_string().starts_with("Invalid YAML:"));
+        assert!(err.to_string().contains("line"));
+    }
+}
@@ -8,9 +8,34 @@ pub enum MoldError {
     #[error("Invalid JSON: {0}")]
     JsonParse(#[from] serde_json::Error),
 
+    #[error("Invalid YAML: {0}")]
+    YamlParse(#[from] serde_yaml_ng::Error),
+
+    #[error("Invalid CSV: {0}")]
+    CsvParse(#[from] csv::Error),
+
+    #[error("Invalid JSON5 at line {line}, column {column}: {message}")]
+    Syntax {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+
     #[error("Root must be an object, got {0}")]
     InvalidRoot(String),
 
+    #[error("Invalid pattern for format '{name}': {reason}")]
+    InvalidPattern { name: String, reason: String },
+
+    #[error("Invalid name for format '{0}': must be a TypeScript identifier (e.g., OrderNumber)")]
+    InvalidFormatName(String),
+
+    #[error("Unknown built-in format '{name}' (expected one of: {expected})")]
+    UnknownFormat { name: String, expected: String },
+
+    #[error("No samples found in input")]
+    NoSamples,
+
     #[error("Failed to write output: {0}")]
     WriteError(String),
 
@@ -6,6 +6,10 @@ pub struct FieldMetadata {
     pub default_value: Option<String>,
     pub is_unique: bool,
     pub is_readonly: bool,
+    /// Model a foreign-key field points at (e.g., `User` for `userId`)
+    pub references: Option<String>,
+    /// Bounds observed across the samples, when constraint inference is on
+    pub constraints: Constraints,
 }
 
 impl FieldMetadata {
@@ -14,11 +18,41 @@ impl FieldMetadata {
     }
 }
 
+/// Smallest and largest values, string lengths and array sizes seen for a
+/// field. `None` means unbounded.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Constraints {
+    pub minimum: Option<f64>,
+    pub maximum: Option<f64>,
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+    pub min_items: Option<usize>,
+    pub max_items: Option<usize>,
+}
+
+impl Constraints {
+    /// The tightest bounds that still cover both `self` and `other`
+    pub fn widen(&self, other: &Self) -> Self {
+        fn pick<T: Copy>(a: Option<T>, b: Option<T>, f: fn(T, T) -> T) -> Option<T> {
+            Some(f(a?, b?))
+        }
+        Self {
+            minimum: pick(self.minimum, other.minimum, f64::min),
+            maximum: pick(self.maximum, other.maximum, f64::max),
+            min_length: pick(self.min_length, other.min_length, usize::min),
+            max_length: pick(self.max_length, other.max_length, usize::max),
+            min_items: pick(self.min_items, other.min_items, usize::min),
+            max_items: pick(self.max_items, other.max_items, usize::max),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Field {
     pub name: String,
     pub field_type: SchemaType,
     pub optional: bool,
+    pub nullable: bool,
     pub metadata: FieldMetadata,
 }
 
@@ -28,6 +62,7 @@ impl Field {
             name: name.into(),
             field_type,
             optional: false,
+            nullable: false,
             metadata: FieldMetadata::default(),
         }
     }
@@ -37,8 +72,38 @@ impl Field {
         self
     }
 
+    pub fn nullable(mut self) -> Self {
+        self.nullable = true;
+        self
+    }
+
     pub fn with_metadata(mut self, metadata: FieldMetadata) -> Self {
         self.metadata = metadata;
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_widen_constraints() {
+        let a = Constraints {
+            minimum: Some(1.0),
+            maximum: Some(5.0),
+            max_length: Some(3),
+            ..Default::default()
+        };
+        let b = Constraints {
+            minimum: Some(-2.0),
+            maximum: Some(4.0),
+            ..Default::default()
+        };
+        let widened = a.widen(&b);
+        assert_eq!(widened.minimum, Some(-2.0));
+        assert_eq!(widened.maximum, Some(5.0));
+        // Unbounded on one side stays unbounded
+        assert_eq!(widened.max_length, None);
+    }
+}
@@ -0,0 +1,22 @@
+use serde::Deserialize;
+
+/// A user-defined string format (e.g., order numbers), matched by regex
+/// before the built-in formats
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CustomFormat {
+    /// Type name, used for the TypeScript branded alias
+    pub name: String,
+    /// Regex every value of the field must match
+    pub pattern: String,
+    /// Prisma scalar type
+    #[serde(default = "default_prisma_type")]
+    pub prisma_type: String,
+    /// Extra Prisma attribute, e.g. `@db.VarChar(12)`
+    #[serde(default)]
+    pub prisma_attribute: Option<String>,
+}
+
+fn default_prisma_type() -> String {
+    "String".to_string()
+}
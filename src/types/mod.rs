@@ -1,9 +1,11 @@
 mod error;
 mod field;
+mod format;
 mod object;
 mod schema;
 
 pub use error::MoldError;
-pub use field::{Field, FieldMetadata};
-pub use object::{NestedType, ObjectType, Schema};
+pub use field::{Constraints, Field, FieldMetadata};
+pub use format::CustomFormat;
+pub use object::{KeyOrder, NestedType, ObjectType, Schema};
 pub use schema::SchemaType;
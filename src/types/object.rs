@@ -1,5 +1,20 @@
 use super::field::Field;
+use super::format::CustomFormat;
 use super::schema::SchemaType;
+use crate::utils::item_type_name;
+use std::collections::BTreeSet;
+
+/// Order of the fields within each generated type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum KeyOrder {
+    /// Sorted by name
+    #[default]
+    Alphabetical,
+    /// As the keys first appear in the input
+    Source,
+    /// Required fields before optional ones, each group in source order
+    RequiredFirst,
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ObjectType {
@@ -14,12 +29,31 @@ impl ObjectType {
     pub fn empty() -> Self {
         Self { fields: vec![] }
     }
+
+    /// Reorder the fields. Fields are kept in source order until this is called.
+    pub fn sort_fields(&mut self, order: KeyOrder) {
+        match order {
+            KeyOrder::Alphabetical => self.fields.sort_by(|a, b| a.name.cmp(&b.name)),
+            KeyOrder::Source => {}
+            KeyOrder::RequiredFirst => self.fields.sort_by_key(|f| f.optional),
+        }
+    }
+
+    /// The value of `field` if it is a single string literal (a union variant's tag)
+    pub fn literal_value(&self, field: &str) -> Option<&str> {
+        match &self.fields.iter().find(|f| f.name == field)?.field_type {
+            SchemaType::Enum(values) if values.len() == 1 => Some(&values[0]),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct NestedType {
     pub name: String,
     pub object: ObjectType,
+    /// Path-derived names of the types that were merged into this one
+    pub aliases: Vec<String>,
 }
 
 impl NestedType {
@@ -27,8 +61,14 @@ impl NestedType {
         Self {
             name: name.into(),
             object,
+            aliases: vec![],
         }
     }
+
+    pub fn with_aliases(mut self, aliases: Vec<String>) -> Self {
+        self.aliases = aliases;
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -51,4 +91,110 @@ impl Schema {
         self.nested_types = nested_types;
         self
     }
+
+    /// Whether the root is an array of objects (e.g., a list endpoint)
+    pub fn is_array_root(&self) -> bool {
+        matches!(
+            &self.root_type,
+            SchemaType::Array(inner)
+                if matches!(**inner, SchemaType::Object(_) | SchemaType::DiscriminatedUnion { .. })
+        )
+    }
+
+    /// The discriminated union emitted as the root type: the root itself, or
+    /// the element type of an array root (e.g., a list of events)
+    pub fn root_union(&self) -> Option<&SchemaType> {
+        let element = match &self.root_type {
+            SchemaType::Array(inner) => inner.as_ref(),
+            other => other,
+        };
+        match element {
+            SchemaType::DiscriminatedUnion { .. } => Some(element),
+            _ => None,
+        }
+    }
+
+    /// The object emitted as the root model: the root itself, or the element
+    /// type of an array root
+    pub fn root_object(&self) -> Option<&ObjectType> {
+        match &self.root_type {
+            SchemaType::Object(obj) => Some(obj),
+            SchemaType::Array(inner) => match inner.as_ref() {
+                SchemaType::Object(obj) => Some(obj),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Name of the root model: the schema name, or its singular form for
+    /// array roots (e.g., "Users" → "User")
+    pub fn root_object_name(&self) -> String {
+        if self.is_array_root() {
+            item_type_name(&self.name)
+        } else {
+            self.name.clone()
+        }
+    }
+
+    /// Names of the types that recursive fields point back to
+    pub fn recursive_type_names(&self) -> BTreeSet<String> {
+        let mut names = BTreeSet::new();
+        self.visit_types(&mut |t| {
+            if let SchemaType::Ref(name) = t {
+                names.insert(name.clone());
+            }
+        });
+        names
+    }
+
+    /// User-defined formats used anywhere in the schema, first use first
+    pub fn custom_formats(&self) -> Vec<&CustomFormat> {
+        let mut formats: Vec<&CustomFormat> = Vec::new();
+        self.visit_types(&mut |t| {
+            if let SchemaType::Custom(format) = t {
+                if !formats.iter().any(|f| f.name == format.name) {
+                    formats.push(format);
+                }
+            }
+        });
+        formats
+    }
+
+    /// Call `f` on every type in the root and the nested types
+    fn visit_types<'a>(&'a self, f: &mut impl FnMut(&'a SchemaType)) {
+        visit_type(&self.root_type, f);
+        for nt in &self.nested_types {
+            for field in &nt.object.fields {
+                visit_type(&field.field_type, f);
+            }
+        }
+    }
+}
+
+fn visit_type<'a>(schema_type: &'a SchemaType, f: &mut impl FnMut(&'a SchemaType)) {
+    f(schema_type);
+    match schema_type {
+        SchemaType::Object(obj) => {
+            for field in &obj.fields {
+                visit_type(&field.field_type, f);
+            }
+        }
+        SchemaType::Array(inner) | SchemaType::Optional(inner) | SchemaType::Map(inner) => {
+            visit_type(inner, f)
+        }
+        SchemaType::Union(types) | SchemaType::Tuple(types) => {
+            for t in types {
+                visit_type(t, f);
+            }
+        }
+        SchemaType::DiscriminatedUnion { variants, .. } => {
+            for variant in variants {
+                for field in &variant.fields {
+                    visit_type(&field.field_type, f);
+                }
+            }
+        }
+        _ => {}
+    }
 }
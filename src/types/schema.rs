@@ -1,3 +1,4 @@
+use super::format::CustomFormat;
 use super::object::ObjectType;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -5,17 +6,63 @@ pub enum SchemaType {
     String,
     Number,
     Integer,
+    /// A whole number beyond 32 bits that is still exact in JavaScript
+    Int64,
+    /// A whole number beyond JavaScript's safe-integer range (2^53 - 1)
+    BigInt,
+    /// A number to store exactly, e.g. money marked `@type decimal`
+    Decimal,
     Boolean,
     Null,
     DateTime,
+    /// Unix timestamp in seconds, e.g. `1697500000`
+    EpochSeconds,
+    /// Unix timestamp in milliseconds, e.g. `1697500000123`
+    EpochMillis,
     Date,
     Uuid,
     Email,
     Url,
+    Ipv4,
+    Ipv6,
+    Ulid,
+    Cuid,
+    /// Time of day (e.g., `14:30:00`)
+    Time,
+    /// ISO 8601 duration (e.g., `PT1H30M`)
+    Duration,
+    HexColor,
+    Semver,
+    Base64,
+    Jwt,
+    /// E.164 phone number (e.g., `+14155550123`)
+    Phone,
+    /// A whole number sent as a string (e.g., `"42"`)
+    NumericString,
+    /// A whole number sent as a string that overflows 32 bits (e.g., `"4155550123"`)
+    WideNumericString,
+    /// A decimal sent as a string (e.g., `"19.99"`), usually money
+    DecimalString,
+    /// `"true"` or `"false"`
+    BooleanString,
+    /// A string matching a user-defined format
+    Custom(Box<CustomFormat>),
     Enum(Vec<String>),
     Array(Box<SchemaType>),
+    /// A fixed-length array with one type per position (e.g., `[lat, lng]`)
+    Tuple(Vec<SchemaType>),
     Object(ObjectType),
     Optional(Box<SchemaType>),
     Union(Vec<SchemaType>),
+    /// Objects told apart by a tag field; each variant carries its tag value
+    /// as a single-value `Enum` (e.g., `type: "click"`)
+    DiscriminatedUnion {
+        tag: String,
+        variants: Vec<ObjectType>,
+    },
+    /// An object used as a dictionary: arbitrary keys, values of one type
+    Map(Box<SchemaType>),
+    /// A reference to a named type (e.g., `Comment` inside its own `replies`)
+    Ref(String),
     Any,
 }
@@ -113,6 +113,72 @@ pub fn path_to_type_name(path: &[String]) -> String {
         .join("")
 }
 
+/// Split a PascalCase name into its words (e.g., "UserBillingAddress" → ["User", "Billing", "Address"])
+pub fn split_pascal_words(s: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    for c in s.chars() {
+        match words.last_mut() {
+            Some(word) if !c.is_uppercase() => word.push(c),
+            _ => words.push(c.to_string()),
+        }
+    }
+    words
+}
+
+/// Naive English singularization of a PascalCase name (e.g., "Categories" → "Category")
+pub fn singularize(s: &str) -> String {
+    let lower = s.to_lowercase();
+    if lower.ends_with("ies") && s.len() > 3 {
+        format!("{}y", &s[..s.len() - 3])
+    } else if ["sses", "xes", "ches", "shes", "zes"]
+        .iter()
+        .any(|suffix| lower.ends_with(suffix))
+    {
+        s[..s.len() - 2].to_string()
+    } else if lower.ends_with('s')
+        && !["ss", "us", "is"]
+            .iter()
+            .any(|suffix| lower.ends_with(suffix))
+        && s.len() > 1
+    {
+        s[..s.len() - 1].to_string()
+    } else {
+        s.to_string()
+    }
+}
+
+/// Name of the element type of a collection (e.g., "Users" → "User", "Data" → "DataItem")
+pub fn item_type_name(collection: &str) -> String {
+    let singular = singularize(collection);
+    if singular == collection {
+        format!("{}Item", collection)
+    } else {
+        singular
+    }
+}
+
+/// Quote a value as a JSON/JavaScript string literal (e.g., `say "hi"` → `"say \"hi\""`)
+pub fn quote_string(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_else(|_| format!("\"{}\"", s))
+}
+
+/// An `@default` value as a literal: valid JSON is kept as written and bare
+/// text becomes a string. Function calls such as `now()` give `None`.
+pub fn default_literal(value: &str) -> Option<String> {
+    if value.ends_with("()") {
+        return None;
+    }
+    match serde_json::from_str::<serde_json::Value>(value) {
+        Ok(_) => Some(value.to_string()),
+        Err(_) => Some(quote_string(value)),
+    }
+}
+
+/// Free text for a single-line comment, with line breaks collapsed into spaces
+pub fn comment_text(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -149,4 +215,54 @@ mod tests {
             "ProfileContact"
         );
     }
+
+    #[test]
+    fn test_quote_string() {
+        assert_eq!(quote_string("active"), "\"active\"");
+        assert_eq!(quote_string("say \"hi\""), "\"say \\\"hi\\\"\"");
+    }
+
+    #[test]
+    fn test_split_pascal_words() {
+        assert_eq!(
+            split_pascal_words("UserBillingAddress"),
+            vec!["User", "Billing", "Address"]
+        );
+        assert_eq!(split_pascal_words("Item"), vec!["Item"]);
+        assert!(split_pascal_words("").is_empty());
+    }
+
+    #[test]
+    fn test_singularize() {
+        assert_eq!(singularize("Users"), "User");
+        assert_eq!(singularize("Categories"), "Category");
+        assert_eq!(singularize("Boxes"), "Box");
+        assert_eq!(singularize("Matches"), "Match");
+        assert_eq!(singularize("Addresses"), "Address");
+        assert_eq!(singularize("Status"), "Status");
+        assert_eq!(singularize("Analysis"), "Analysis");
+        assert_eq!(singularize("User"), "User");
+    }
+
+    #[test]
+    fn test_item_type_name() {
+        assert_eq!(item_type_name("Users"), "User");
+        assert_eq!(item_type_name("Data"), "DataItem");
+        assert_eq!(item_type_name("Root"), "RootItem");
+    }
+
+    #[test]
+    fn test_default_literal() {
+        assert_eq!(default_literal("0").as_deref(), Some("0"));
+        assert_eq!(default_literal("\"draft\"").as_deref(), Some("\"draft\""));
+        assert_eq!(default_literal("draft").as_deref(), Some("\"draft\""));
+        assert_eq!(default_literal("true").as_deref(), Some("true"));
+        assert_eq!(default_literal("now()"), None);
+    }
+
+    #[test]
+    fn test_comment_text() {
+        assert_eq!(comment_text("Primary\n   contact"), "Primary contact");
+        assert_eq!(comment_text("ends */ here"), "ends */ here");
+    }
 }
@@ -1,6 +1,11 @@
 use mold_cli::generators::{Generator, GeneratorConfig, PrismaGenerator, TypeScriptGenerator, ZodGenerator};
-use mold_cli::parser::{parse_json_string, parse_json_value};
-use mold_cli::types::SchemaType;
+use mold_cli::parser::{
+    parse_csv_string, parse_json_reader, parse_json_string, parse_json_string_with_config,
+    parse_json_value, parse_lenient_annotated, parse_lenient_string, parse_ndjson_reader,
+    parse_yaml_string, CsvOptions, InferenceConfig, Sampling, SchemaBuilder, SchemaReport,
+    StringPatterns,
+};
+use mold_cli::types::{CustomFormat, KeyOrder, SchemaType};
 
 // =============================================================================
 // End-to-end: simple.json
@@ -100,8 +105,10 @@ fn test_nested_json_flat_mode() {
     assert!(schema.nested_types.is_empty());
 
     let gen = TypeScriptGenerator::new();
-    let mut config = GeneratorConfig::default();
-    config.flat_mode = true;
+    let config = GeneratorConfig {
+        flat_mode: true,
+        ..Default::default()
+    };
     let output = gen.generate(&schema, &config).unwrap();
 
     // Should only have one interface
@@ -230,6 +237,214 @@ fn test_blog_post_all_generators_succeed() {
     assert!(prisma.is_ok(), "Prisma generation failed");
 }
 
+// =============================================================================
+// Sparse array elements
+// =============================================================================
+
+const SPARSE_JSON: &str = r#"{
+    "users": [
+        { "id": 1, "name": "Alice", "nickname": "ali" },
+        { "id": 2, "name": "Bob" },
+        { "id": 3, "name": "Carol", "age": 41 }
+    ]
+}"#;
+
+#[test]
+fn test_sparse_array_objects_merge_into_one_type() {
+    let schema = parse_json_string(SPARSE_JSON, "Team", false).unwrap();
+
+    assert_eq!(schema.nested_types.len(), 1);
+    assert_eq!(schema.nested_types[0].name, "TeamUsersItem");
+
+    let config = GeneratorConfig::default();
+    let ts = TypeScriptGenerator::new()
+        .generate(&schema, &config)
+        .unwrap();
+    assert!(ts.contains("users: TeamUsersItem[]"));
+    assert!(ts.contains("id: number;"));
+    assert!(ts.contains("nickname?: string;"));
+    assert!(ts.contains("age?: number;"));
+    assert!(!ts.contains(" | {"));
+
+    let zod = ZodGenerator::new().generate(&schema, &config).unwrap();
+    assert!(zod.contains("users: z.array(TeamUsersItemSchema)"));
+    assert!(zod.contains("nickname: z.string().optional()"));
+
+    let prisma = PrismaGenerator::new().generate(&schema, &config).unwrap();
+    assert!(prisma.contains("nickname String?"));
+    assert!(prisma.contains("age Int?"));
+}
+
+// =============================================================================
+// Nullable vs optional
+// =============================================================================
+
+const NULLABLE_JSON: &str = r#"{
+    "users": [
+        { "id": 1, "bio": null, "nickname": "ali" },
+        { "id": 2, "bio": "Writes Rust" },
+        { "id": 3, "bio": "Reads Rust", "nickname": null }
+    ]
+}"#;
+
+#[test]
+fn test_null_and_value_infers_nullable_field() {
+    let schema = parse_json_string(NULLABLE_JSON, "Team", false).unwrap();
+    let item = &schema.nested_types[0].object;
+    let bio = item.fields.iter().find(|f| f.name == "bio").unwrap();
+    assert_eq!(bio.field_type, SchemaType::String);
+    assert!(bio.nullable);
+    assert!(!bio.optional);
+
+    let config = GeneratorConfig::default();
+    let ts = TypeScriptGenerator::new()
+        .generate(&schema, &config)
+        .unwrap();
+    assert!(ts.contains("bio: string | null;"));
+    assert!(ts.contains("nickname?: string | null;"));
+
+    let zod = ZodGenerator::new().generate(&schema, &config).unwrap();
+    assert!(zod.contains("bio: z.string().nullable(),"));
+    assert!(zod.contains("nickname: z.string().nullish(),"));
+
+    let prisma = PrismaGenerator::new().generate(&schema, &config).unwrap();
+    assert!(prisma.contains("bio String?"));
+    assert!(prisma.contains("nickname String?"));
+}
+
+#[test]
+fn test_null_only_field_is_nullable_unknown() {
+    let schema = parse_json_string(r#"{"name": "a", "bio": null}"#, "User", false).unwrap();
+    let root = schema.root_object().unwrap();
+    let bio = root.fields.iter().find(|f| f.name == "bio").unwrap();
+    assert_eq!(bio.field_type, SchemaType::Any);
+    assert!(bio.nullable);
+
+    let config = GeneratorConfig::default();
+    let ts = TypeScriptGenerator::new()
+        .generate(&schema, &config)
+        .unwrap();
+    assert!(ts.contains("bio: unknown | null;"));
+
+    let zod = ZodGenerator::new().generate(&schema, &config).unwrap();
+    assert!(zod.contains("bio: z.unknown().nullable(),"));
+
+    let prisma = PrismaGenerator::new().generate(&schema, &config).unwrap();
+    assert!(prisma.contains("bio Json?"));
+}
+
+// =============================================================================
+// Enum inference
+// =============================================================================
+
+const ORDERS_JSON: &str = r#"{
+    "orders": [
+        { "id": 1, "status": "active", "note": "first" },
+        { "id": 2, "status": "pending", "note": "second" },
+        { "id": 3, "status": "active", "note": "third" },
+        { "id": 4, "status": "active", "note": "fourth" }
+    ]
+}"#;
+
+#[test]
+fn test_enum_inference_end_to_end() {
+    let inference = InferenceConfig {
+        detect_enums: true,
+        ..Default::default()
+    };
+    let schema = parse_json_string_with_config(ORDERS_JSON, "Shop", &inference).unwrap();
+    let config = GeneratorConfig::default();
+
+    let ts = TypeScriptGenerator::new()
+        .generate(&schema, &config)
+        .unwrap();
+    assert!(ts.contains("status: \"active\" | \"pending\";"));
+    assert!(ts.contains("note: string;"));
+
+    let zod = ZodGenerator::new().generate(&schema, &config).unwrap();
+    assert!(zod.contains("status: z.enum([\"active\", \"pending\"]),"));
+
+    let prisma = PrismaGenerator::new().generate(&schema, &config).unwrap();
+    assert!(prisma.contains("status ShopOrdersItemStatus"));
+    assert!(prisma.contains("enum ShopOrdersItemStatus {"));
+}
+
+#[test]
+fn test_enum_inference_off_by_default() {
+    let schema = parse_json_string(ORDERS_JSON, "Shop", false).unwrap();
+    let ts = TypeScriptGenerator::new()
+        .generate(&schema, &GeneratorConfig::default())
+        .unwrap();
+    assert!(ts.contains("status: string;"));
+}
+
+// =============================================================================
+// NDJSON input
+// =============================================================================
+
+#[test]
+fn test_ndjson_file_folds_into_one_schema() {
+    let dir = tempfile::tempdir().unwrap();
+    let file_path = dir.path().join("events.ndjson");
+    std::fs::write(
+        &file_path,
+        "{\"id\": 1, \"kind\": \"click\", \"target\": {\"x\": 1}}\n\
+         {\"id\": 2, \"kind\": \"view\", \"duration\": 3.5}\n\
+         {broken\n\
+         {\"id\": 3, \"kind\": \"view\", \"target\": null}\n",
+    )
+    .unwrap();
+
+    let reader = std::io::BufReader::new(std::fs::File::open(&file_path).unwrap());
+    let (schema, malformed) =
+        parse_ndjson_reader(reader, "Event", &InferenceConfig::default()).unwrap();
+
+    assert_eq!(malformed.len(), 1);
+    assert_eq!(malformed[0].line, 3);
+
+    let config = GeneratorConfig::default();
+    let ts = TypeScriptGenerator::new()
+        .generate(&schema, &config)
+        .unwrap();
+    // Clicks and views differ in shape, so the `kind` tag splits them
+    assert!(ts.contains("type Event = EventClick | EventView;"));
+    assert!(ts.contains("id: number;"));
+    assert!(ts.contains("duration?: number;"));
+    assert!(ts.contains("target: EventClickTarget;"));
+}
+
+// =============================================================================
+// Merging multiple samples
+// =============================================================================
+
+#[test]
+fn test_merge_samples_into_one_schema() {
+    let samples = [
+        r#"{"id": 1, "score": 10, "address": {"city": "Oslo"}}"#,
+        r#"{"id": 2, "score": 9.5, "address": {"city": "Rome", "zip": "00100"}, "nick": "r"}"#,
+        r#"{"id": "u-3", "address": {"city": "Lima"}}"#,
+    ];
+    let mut builder = SchemaBuilder::new("User", InferenceConfig::default());
+    for sample in samples {
+        builder.add_sample(&serde_json::from_str(sample).unwrap());
+    }
+    let schema = builder.finish().unwrap();
+
+    assert_eq!(schema.nested_types.len(), 1);
+    assert_eq!(schema.nested_types[0].name, "UserAddress");
+
+    let config = GeneratorConfig::default();
+    let ts = TypeScriptGenerator::new()
+        .generate(&schema, &config)
+        .unwrap();
+    assert!(ts.contains("id: number | string;"));
+    assert!(ts.contains("score?: number;"));
+    assert!(ts.contains("nick?: string;"));
+    assert!(ts.contains("address: UserAddress;"));
+    assert!(ts.contains("zip?: string;"));
+    assert_eq!(ts.matches("interface ").count(), 2);
+}
+
 // =============================================================================
 // Semantic type detection end-to-end
 // =============================================================================
@@ -308,6 +523,619 @@ fn test_empty_object_is_valid() {
     }
 }
 
+#[test]
+fn test_array_root_generates_element_and_collection_types() {
+    let json = r#"[
+        {"id": 1, "name": "Ada", "address": {"city": "London"}},
+        {"id": 2, "name": "Linus"}
+    ]"#;
+    let schema = parse_json_string(json, "Users", false).unwrap();
+    let config = GeneratorConfig::default();
+
+    let ts = TypeScriptGenerator::new()
+        .generate(&schema, &config)
+        .unwrap();
+    assert!(ts.contains("interface UserAddress {"));
+    assert!(ts.contains("interface User {"));
+    assert!(ts.contains("address?: UserAddress;"));
+    assert!(ts.contains("type Users = User[];"));
+
+    let zod = ZodGenerator::new().generate(&schema, &config).unwrap();
+    assert!(zod.contains("const UserSchema = z.object({"));
+    assert!(zod.contains("const UsersSchema = z.array(UserSchema);"));
+
+    let prisma = PrismaGenerator::new().generate(&schema, &config).unwrap();
+    assert!(prisma.contains("model User {"));
+    assert!(!prisma.contains("model Users {"));
+}
+
+#[test]
+fn test_identical_nested_shapes_generate_one_type() {
+    let json = r#"{
+        "billingAddress": {"street": "1 Main St", "city": "Oslo"},
+        "shippingAddress": {"street": "2 Side St", "city": "Bergen", "zip": "5003"}
+    }"#;
+    let schema = parse_json_string(json, "Order", false).unwrap();
+    let config = GeneratorConfig::default();
+
+    let ts = TypeScriptGenerator::new()
+        .generate(&schema, &config)
+        .unwrap();
+    assert_eq!(ts.matches("interface Address {").count(), 1);
+    assert!(ts.contains("billingAddress: Address;"));
+    assert!(ts.contains("shippingAddress: Address;"));
+    assert!(ts.contains("zip?: string;"));
+    assert!(!ts.contains("OrderBillingAddress"));
+
+    let zod = ZodGenerator::new().generate(&schema, &config).unwrap();
+    assert!(zod.contains("billingAddress: AddressSchema,"));
+    assert!(zod.contains("shippingAddress: AddressSchema,"));
+}
+
+#[test]
+fn test_recursive_tree_emits_self_reference() {
+    let json = r#"{
+        "name": "Electronics",
+        "children": [
+            {"name": "Phones", "children": [{"name": "Android", "children": []}]},
+            {"name": "Laptops", "children": []}
+        ]
+    }"#;
+    let schema = parse_json_string(json, "Category", false).unwrap();
+    assert!(schema.nested_types.is_empty());
+    let config = GeneratorConfig::default();
+
+    let ts = TypeScriptGenerator::new()
+        .generate(&schema, &config)
+        .unwrap();
+    assert!(ts.contains("children: Category[];"));
+    assert!(!ts.contains("CategoryChildrenItem"));
+
+    let zod = ZodGenerator::new().generate(&schema, &config).unwrap();
+    assert!(zod.contains("const CategorySchema: z.ZodType<Category> = z.object({"));
+    assert!(zod.contains("children: z.array(z.lazy(() => CategorySchema)),"));
+
+    let prisma = PrismaGenerator::new().generate(&schema, &config).unwrap();
+    assert!(prisma.contains("children Category[] @relation(\"CategoryChildren\")"));
+    assert!(prisma.contains("childrenParentId Int?"));
+}
+
+#[test]
+fn test_id_keyed_object_becomes_record() {
+    let json = r#"{
+        "users": {
+            "u_123": {"name": "Ada", "role": "admin"},
+            "u_456": {"name": "Linus", "role": "user"},
+            "u_789": {"name": "Grace", "role": "user"}
+        }
+    }"#;
+    let schema = parse_json_string(json, "Directory", false).unwrap();
+    let config = GeneratorConfig::default();
+
+    let ts = TypeScriptGenerator::new()
+        .generate(&schema, &config)
+        .unwrap();
+    assert!(ts.contains("interface DirectoryUsersValue {"));
+    assert!(ts.contains("users: Record<string, DirectoryUsersValue>;"));
+    assert!(!ts.contains("u_123"));
+
+    let zod = ZodGenerator::new().generate(&schema, &config).unwrap();
+    assert!(zod.contains("users: z.record(z.string(), DirectoryUsersValueSchema),"));
+
+    let prisma = PrismaGenerator::new().generate(&schema, &config).unwrap();
+    assert!(prisma.contains("users Json"));
+    assert!(!prisma.contains("model DirectoryUsersValue"));
+    assert!(!prisma.contains("enum DirectoryUsersValue"));
+}
+
+#[test]
+fn test_map_path_override() {
+    let json = r#"{"limits": {"free": 10, "pro": 100}}"#;
+    let config = InferenceConfig {
+        map_paths: vec!["limits".to_string()],
+        ..Default::default()
+    };
+    let schema = parse_json_string_with_config(json, "Plan", &config).unwrap();
+
+    let ts = TypeScriptGenerator::new()
+        .generate(&schema, &GeneratorConfig::default())
+        .unwrap();
+    assert!(ts.contains("limits: Record<string, number>;"));
+}
+
+#[test]
+fn test_fixed_length_arrays_become_tuples() {
+    let json = r#"[
+        {"name": "Rome", "location": [12.5, 41.9], "tag": ["capital", 1]},
+        {"name": "Paris", "location": [2.35, 48.85], "tag": ["capital", 2]}
+    ]"#;
+    let schema = parse_json_string(json, "Cities", false).unwrap();
+    let config = GeneratorConfig::default();
+
+    let ts = TypeScriptGenerator::new()
+        .generate(&schema, &config)
+        .unwrap();
+    assert!(ts.contains("location: [number, number];"));
+    assert!(ts.contains("tag: [string, number];"));
+
+    let zod = ZodGenerator::new().generate(&schema, &config).unwrap();
+    assert!(zod.contains("location: z.tuple([z.number(), z.number()]),"));
+    assert!(zod.contains("tag: z.tuple([z.string(), z.number().int()]),"));
+
+    let prisma = PrismaGenerator::new().generate(&schema, &config).unwrap();
+    assert!(prisma.contains("location Json"));
+}
+
+#[test]
+fn test_tagged_events_become_discriminated_union() {
+    let json = r#"[
+        {"type": "click", "x": 10, "y": 20},
+        {"type": "view", "page": "/home", "duration": 3.5},
+        {"type": "click", "x": 5, "y": 8}
+    ]"#;
+    let schema = parse_json_string(json, "Events", false).unwrap();
+    let config = GeneratorConfig::default();
+
+    let ts = TypeScriptGenerator::new()
+        .generate(&schema, &config)
+        .unwrap();
+    assert!(ts.contains("interface EventClick {"));
+    assert!(ts.contains("interface EventView {"));
+    assert!(ts.contains("\"type\": \"click\";"));
+    assert!(ts.contains("type Event = EventClick | EventView;"));
+    assert!(ts.contains("type Events = Event[];"));
+
+    let zod = ZodGenerator::new().generate(&schema, &config).unwrap();
+    assert!(zod.contains(
+        "const EventSchema = z.discriminatedUnion(\"type\", [EventClickSchema, EventViewSchema]);"
+    ));
+
+    let prisma = PrismaGenerator::new().generate(&schema, &config).unwrap();
+    assert!(prisma.contains("model EventClick {"));
+    assert!(prisma.contains("type_ String @default(\"view\")"));
+}
+
+#[test]
+fn test_large_integers_keep_their_precision() {
+    let json = r#"[
+        {"id": 1577836800123456789, "views": 3000000000, "likes": 12},
+        {"id": 1577836800123456790, "views": 42, "likes": 7}
+    ]"#;
+    let schema = parse_json_string(json, "Tweets", false).unwrap();
+    let config = GeneratorConfig::default();
+
+    let ts = TypeScriptGenerator::new()
+        .generate(&schema, &config)
+        .unwrap();
+    assert!(ts.contains("id: bigint;"));
+    assert!(ts.contains("views: number;"));
+
+    let zod = ZodGenerator::new().generate(&schema, &config).unwrap();
+    assert!(zod.contains("id: z.coerce.bigint(),"));
+    assert!(zod.contains("likes: z.number().int(),"));
+
+    let prisma = PrismaGenerator::new().generate(&schema, &config).unwrap();
+    assert!(prisma.contains("id BigInt"));
+    assert!(prisma.contains("views BigInt"));
+    assert!(prisma.contains("likes Int"));
+}
+
+#[test]
+fn test_string_formats_map_to_zod_validators() {
+    let json = r##"{
+        "ip": "10.0.0.1",
+        "requestId": "01ARZ3NDEKTSV4RRFFQ69G5FAV",
+        "opensAt": "09:00:00",
+        "ttl": "PT15M",
+        "color": "#1e90ff",
+        "version": "1.4.2",
+        "phone": "+14155550123"
+    }"##;
+    let schema = parse_json_string(json, "Session", false).unwrap();
+    let config = GeneratorConfig::default();
+
+    let zod = ZodGenerator::new().generate(&schema, &config).unwrap();
+    assert!(zod.contains("ip: z.string().ip({ version: \"v4\" }),"));
+    assert!(zod.contains("requestId: z.string().ulid(),"));
+    assert!(zod.contains("opensAt: z.string().time(),"));
+    assert!(zod.contains("ttl: z.string().duration(),"));
+    assert!(zod.contains("color: z.string().regex("));
+    assert!(zod.contains("phone: z.string().regex("));
+
+    let ts = TypeScriptGenerator::new()
+        .generate(&schema, &config)
+        .unwrap();
+    assert!(ts.contains("version: string;"));
+
+    let prisma = PrismaGenerator::new().generate(&schema, &config).unwrap();
+    assert!(prisma.contains("ttl String"));
+}
+
+#[test]
+fn test_custom_formats_from_config() {
+    let format = CustomFormat {
+        name: "OrderNumber".to_string(),
+        pattern: r"^ORD-\d{8}$".to_string(),
+        prisma_type: "String".to_string(),
+        prisma_attribute: Some("@db.VarChar(12)".to_string()),
+    };
+    let config = InferenceConfig {
+        patterns: StringPatterns::new(vec![format], vec!["email".to_string()]).unwrap(),
+        ..Default::default()
+    };
+    let json = r#"{"orderNo": "ORD-00012345", "handle": "ada@home"}"#;
+    let schema = parse_json_string_with_config(json, "Order", &config).unwrap();
+    let gen_config = GeneratorConfig::default();
+
+    let ts = TypeScriptGenerator::new()
+        .generate(&schema, &gen_config)
+        .unwrap();
+    assert!(ts.contains("type OrderNumber = string & { readonly __brand: \"OrderNumber\" };"));
+    assert!(ts.contains("orderNo: OrderNumber;"));
+    assert!(ts.contains("handle: string;"));
+
+    let zod = ZodGenerator::new().generate(&schema, &gen_config).unwrap();
+    assert!(zod.contains(r"orderNo: z.string().regex(/^ORD-\d{8}$/),"));
+    assert!(zod.contains("handle: z.string(),"));
+
+    let prisma = PrismaGenerator::new()
+        .generate(&schema, &gen_config)
+        .unwrap();
+    assert!(prisma.contains("orderNo String @db.VarChar(12)"));
+}
+
+#[test]
+fn test_stringly_typed_numbers_and_booleans() {
+    let json = r#"{"price": "19.99", "count": "42", "enabled": "false", "zip": "02134", "phone": "4155550123"}"#;
+    let schema = parse_json_string(json, "Product", false).unwrap();
+
+    let prisma = PrismaGenerator::new()
+        .generate(&schema, &GeneratorConfig::default())
+        .unwrap();
+    assert!(prisma.contains("price Decimal"));
+    assert!(prisma.contains("count String"));
+    assert!(prisma.contains("enabled Boolean"));
+    assert!(prisma.contains("zip String"));
+    assert!(prisma.contains("phone String"));
+
+    let config = GeneratorConfig {
+        coerce_strings: true,
+        ..Default::default()
+    };
+    let prisma = PrismaGenerator::new().generate(&schema, &config).unwrap();
+    assert!(prisma.contains("count Int"));
+    // Too large for a 32-bit `Int`
+    assert!(prisma.contains("phone BigInt"));
+
+    let ts = TypeScriptGenerator::new()
+        .generate(&schema, &config)
+        .unwrap();
+    assert!(ts.contains("price: number;"));
+    assert!(ts.contains("enabled: boolean;"));
+    assert!(ts.contains("zip: string;"));
+
+    let zod = ZodGenerator::new().generate(&schema, &config).unwrap();
+    assert!(zod.contains("price: z.coerce.number(),"));
+}
+
+#[test]
+fn test_epoch_timestamps_become_dates() {
+    let json = r#"{"id": 7, "created_at": 1697500000, "expiresAt": 1697500000123}"#;
+    let config = GeneratorConfig::default();
+
+    // Only with detection turned on
+    let plain = parse_json_string(json, "Session", false).unwrap();
+    let prisma = PrismaGenerator::new().generate(&plain, &config).unwrap();
+    assert!(prisma.contains("created_at Int"));
+
+    let inference = InferenceConfig {
+        detect_timestamps: true,
+        ..Default::default()
+    };
+    let schema = parse_json_string_with_config(json, "Session", &inference).unwrap();
+
+    let ts = TypeScriptGenerator::new()
+        .generate(&schema, &config)
+        .unwrap();
+    assert!(ts.contains("/** Unix timestamp in seconds */"));
+    assert!(ts.contains("created_at: number;"));
+
+    let zod = ZodGenerator::new().generate(&schema, &config).unwrap();
+    assert!(zod.contains("created_at: z.number().int().transform((s) => new Date(s * 1000)),"));
+    assert!(zod.contains("expiresAt: z.number().int().transform((ms) => new Date(ms)),"));
+
+    let prisma = PrismaGenerator::new().generate(&schema, &config).unwrap();
+    assert!(prisma.contains("created_at DateTime"));
+    assert!(prisma.contains("expiresAt DateTime"));
+}
+
+#[test]
+fn test_field_names_drive_metadata() {
+    let json = r#"{"id": 1, "slug": "hello", "authorId": 9, "createdAt": "2024-01-15T10:30:00Z"}"#;
+    let schema = parse_json_string(json, "Post", false).unwrap();
+    let config = GeneratorConfig::default();
+
+    let ts = TypeScriptGenerator::new()
+        .generate(&schema, &config)
+        .unwrap();
+    assert!(ts.contains("readonly id: number;"));
+    assert!(ts.contains("readonly createdAt: string;"));
+    assert!(ts.contains("  slug: string;"));
+    assert!(ts.contains("/** References `Author` */"));
+
+    let prisma = PrismaGenerator::new().generate(&schema, &config).unwrap();
+    assert!(prisma.contains("slug String @unique"));
+}
+
+#[test]
+fn test_constraints_from_observed_samples() {
+    let json = r#"[{"name": "Ann", "age": 31}, {"name": "Bartholomew", "age": 7}]"#;
+    let inference = InferenceConfig {
+        infer_constraints: true,
+        ..Default::default()
+    };
+    let schema = parse_json_string_with_config(json, "People", &inference).unwrap();
+    let gen_config = GeneratorConfig::default();
+
+    let zod = ZodGenerator::new().generate(&schema, &gen_config).unwrap();
+    assert!(zod.contains("name: z.string().min(3).max(11),"));
+    assert!(zod.contains("age: z.number().int().min(7).max(31),"));
+
+    let prisma = PrismaGenerator::new()
+        .generate(&schema, &gen_config)
+        .unwrap();
+    assert!(prisma.contains("name String @db.VarChar(11)"));
+
+    let ts = TypeScriptGenerator::new()
+        .generate(&schema, &gen_config)
+        .unwrap();
+    assert!(ts.contains("* @maxLength 11"));
+}
+
+#[test]
+fn test_report_over_merged_samples() {
+    let mut builder = SchemaBuilder::new("User", InferenceConfig::default());
+    builder.add_sample(&serde_json::json!({"id": 1, "role": "admin", "manager": null}));
+    builder.add_sample(&serde_json::json!({"id": 2, "role": "user"}));
+    let (schema, stats) = builder.finish_with_stats().unwrap();
+    let report = SchemaReport::new(&schema, &stats);
+
+    assert_eq!(report.samples, 2);
+    let manager = report.get("manager").unwrap();
+    assert_eq!(manager.presence, Some(0.5));
+    assert_eq!(manager.null_ratio, 1.0);
+
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["paths"][2]["path"], "role");
+    assert_eq!(
+        json["paths"][2]["examples"],
+        serde_json::json!(["\"admin\"", "\"user\""])
+    );
+}
+
+#[test]
+fn test_streaming_matches_in_memory() {
+    let fixtures = [
+        SIMPLE_JSON,
+        NESTED_JSON,
+        ARRAYS_JSON,
+        BLOG_POST_JSON,
+        SPARSE_JSON,
+        NULLABLE_JSON,
+        ORDERS_JSON,
+    ];
+    let config = InferenceConfig {
+        detect_enums: true,
+        infer_constraints: true,
+        ..Default::default()
+    };
+    for json in fixtures {
+        // As the document itself, and as the elements of a top-level array
+        for input in [json.to_string(), format!("[{}, {}]", json, json)] {
+            let streamed =
+                parse_json_reader(input.as_bytes(), "Root", &config, Sampling::All).unwrap();
+            let in_memory = parse_json_string_with_config(&input, "Root", &config).unwrap();
+            assert_eq!(format!("{:?}", streamed), format!("{:?}", in_memory));
+        }
+    }
+}
+
+#[test]
+fn test_key_order_applies_to_every_generator() {
+    let json = r#"[{"zip": "A1", "name": "a", "id": 1}, {"name": "b", "id": 2}]"#;
+    let inference = InferenceConfig {
+        key_order: KeyOrder::RequiredFirst,
+        ..Default::default()
+    };
+    let schema = parse_json_string_with_config(json, "Rows", &inference).unwrap();
+    let config = GeneratorConfig::default();
+    let position = |out: &str, needle: &str| out.find(needle).unwrap();
+
+    let ts = TypeScriptGenerator::new()
+        .generate(&schema, &config)
+        .unwrap();
+    assert!(position(&ts, "name:") < position(&ts, "id:"));
+    assert!(position(&ts, "id:") < position(&ts, "zip?:"));
+
+    let zod = ZodGenerator::new().generate(&schema, &config).unwrap();
+    assert!(position(&zod, "name:") < position(&zod, "id:"));
+    assert!(position(&zod, "id:") < position(&zod, "zip:"));
+
+    let prisma = PrismaGenerator::new().generate(&schema, &config).unwrap();
+    assert!(position(&prisma, "name String") < position(&prisma, "zip String?"));
+}
+
+#[test]
+fn test_lenient_input_matches_strict_json() {
+    let lenient = r#"
+        // A fixture with comments
+        {
+            id: 1,
+            name: 'Ada',
+            /* nested */
+            address: {city: "London", zip: 'N1',},
+            tags: ['a', 'b',],
+        }
+    "#;
+    let strict = r#"{"id": 1, "name": "Ada", "address": {"city": "London", "zip": "N1"}, "tags": ["a", "b"]}"#;
+    let config = InferenceConfig::default();
+
+    let from_lenient = parse_lenient_string(lenient, "User", &config).unwrap();
+    let from_strict = parse_json_string_with_config(strict, "User", &config).unwrap();
+    assert_eq!(format!("{:?}", from_lenient), format!("{:?}", from_strict));
+
+    let err = parse_lenient_string("{\n  id: 1\n  name: 'x'\n}", "User", &config).unwrap_err();
+    assert!(err.to_string().contains("line 3, column 3"));
+}
+
+#[test]
+fn test_comment_annotations_drive_generated_fields() {
+    let fixture = r#"[
+        {
+            "id": 1,
+            "email": "a@b.co", // @unique @description Primary contact
+            "price": "9.99", // @type Decimal
+            "status": "draft", // @default active
+            // @optional
+            "nickname": "Al",
+        },
+    ]"#;
+    let (value, annotations) = parse_lenient_annotated(fixture).unwrap();
+    let mut builder = SchemaBuilder::new("Product", InferenceConfig::default());
+    builder.add_annotations(annotations);
+    builder.add_sample(&value);
+    let schema = builder.finish().unwrap();
+    let config = GeneratorConfig::default();
+
+    let ts = TypeScriptGenerator::new()
+        .generate(&schema, &config)
+        .unwrap();
+    assert!(ts.contains("/** Primary contact */"));
+    assert!(ts.contains("nickname?: string;"));
+
+    let zod = ZodGenerator::new().generate(&schema, &config).unwrap();
+    assert!(zod.contains(r#"email: z.string().email().describe("Primary contact"),"#));
+    assert!(zod.contains(r#"status: z.string().default("active"),"#));
+    assert!(zod.contains("nickname: z.string().optional(),"));
+
+    let prisma = PrismaGenerator::new().generate(&schema, &config).unwrap();
+    assert!(prisma.contains("/// Primary contact\n  email String @unique"));
+    assert!(prisma.contains("price Decimal\n"));
+    assert!(prisma.contains(r#"status String @default("active")"#));
+    assert!(prisma.contains("nickname String?"));
+}
+
+#[test]
+fn test_decimal_annotation_follows_sample_kind() {
+    let fixture = r#"{
+        "price": 19.99, // @type Decimal
+        "total": "39.98", // @type Decimal
+    }"#;
+    let (value, annotations) = parse_lenient_annotated(fixture).unwrap();
+    let mut builder = SchemaBuilder::new("Line", InferenceConfig::default());
+    builder.add_annotations(annotations);
+    builder.add_sample(&value);
+    let schema = builder.finish().unwrap();
+    let obj = schema.root_object().unwrap();
+    assert_eq!(obj.fields[0].field_type, SchemaType::Decimal);
+    assert_eq!(obj.fields[1].field_type, SchemaType::DecimalString);
+    let config = GeneratorConfig::default();
+
+    // The generated types accept the sample they came from
+    let ts = TypeScriptGenerator::new().generate(&schema, &config).unwrap();
+    assert!(ts.contains("price: number;"));
+    assert!(ts.contains("total: string;"));
+
+    let zod = ZodGenerator::new().generate(&schema, &config).unwrap();
+    assert!(zod.contains("price: z.number(),"));
+    assert!(zod.contains(r"total: z.string().regex(/^-?\d+(\.\d+)?$/),"));
+
+    let prisma = PrismaGenerator::new().generate(&schema, &config).unwrap();
+    assert!(prisma.contains("price Decimal\n"));
+    assert!(prisma.contains("total Decimal\n"));
+}
+
+#[test]
+fn test_annotation_descriptions_stay_inside_comments() {
+    let fixture = r#"{
+        "a": 1, // @description closes */ early
+        /* @description spans
+           two lines */
+        "b": 2
+    }"#;
+    let (value, annotations) = parse_lenient_annotated(fixture).unwrap();
+    let mut builder = SchemaBuilder::new("Doc", InferenceConfig::default());
+    builder.add_annotations(annotations);
+    builder.add_sample(&value);
+    let schema = builder.finish().unwrap();
+    let config = GeneratorConfig::default();
+
+    let ts = TypeScriptGenerator::new()
+        .generate(&schema, &config)
+        .unwrap();
+    assert!(ts.contains(r"/** closes *\/ early */"));
+    assert!(ts.contains("/** spans two lines */"));
+
+    let prisma = PrismaGenerator::new().generate(&schema, &config).unwrap();
+    assert!(prisma.contains("/// closes */ early\n"));
+    assert!(prisma.contains("/// spans two lines\n"));
+}
+
+#[test]
+fn test_yaml_documents_match_json_samples() {
+    let yaml = "\
+---
+id: 1
+name: Ada
+joined: 2023-01-15 10:30:00
+address: {city: London}
+---
+id: 2
+name: Grace
+joined: 2023-02-01T09:00:00Z
+address: {city: Paris}
+";
+    let json = r#"{"id": 1, "name": "Ada", "joined": "2023-01-15T10:30:00", "address": {"city": "London"}}"#;
+    let config = InferenceConfig::default();
+
+    let from_yaml = parse_yaml_string(yaml, "User", &config).unwrap();
+    let from_json = parse_json_string_with_config(json, "User", &config).unwrap();
+    assert_eq!(format!("{:?}", from_yaml), format!("{:?}", from_json));
+
+    let ts = TypeScriptGenerator::new()
+        .generate(&from_yaml, &GeneratorConfig::default())
+        .unwrap();
+    assert!(ts.contains("joined: string;"));
+    assert!(ts.contains("address: UserAddress;"));
+}
+
+#[test]
+fn test_csv_rows_to_prisma_and_zod() {
+    let csv = "\
+id;email;age;active;signup;score
+1;a@b.co;36;true;2023-01-15;9.5
+2;c@d.io;;false;2023-02-01;7
+";
+    let options = CsvOptions {
+        delimiter: b';',
+        has_headers: true,
+    };
+    let schema = parse_csv_string(csv, "User", &InferenceConfig::default(), &options).unwrap();
+    let config = GeneratorConfig::default();
+
+    let prisma = PrismaGenerator::new().generate(&schema, &config).unwrap();
+    assert!(prisma.contains("model User {"));
+    assert!(prisma.contains("age Int?"));
+    assert!(prisma.contains("active Boolean"));
+    assert!(prisma.contains("email String @unique"));
+    assert!(prisma.contains("score Float"));
+    assert!(prisma.contains("signup DateTime"));
+
+    let zod = ZodGenerator::new().generate(&schema, &config).unwrap();
+    assert!(zod.contains("age: z.number().int().optional(),"));
+    assert!(zod.contains("signup: z.string().date(),"));
+}
+
 #[test]
 fn test_parse_json_value_directly() {
     let value: serde_json::Value = serde_json::json!({"key": "value"});
@@ -320,6 +1148,7 @@ fn test_parse_json_value_directly() {
 // =============================================================================
 
 #[test]
+#[allow(clippy::field_reassign_with_default)]
 fn test_typescript_export_and_readonly_combined() {
     let schema = parse_json_string(SIMPLE_JSON, "User", false).unwrap();
     let gen = TypeScriptGenerator::new();
@@ -334,6 +1163,7 @@ fn test_typescript_export_and_readonly_combined() {
 }
 
 #[test]
+#[allow(clippy::field_reassign_with_default)]
 fn test_zod_strict_with_nested_types() {
     let schema = parse_json_string(BLOG_POST_JSON, "BlogPost", false).unwrap();
     let gen = ZodGenerator::new();